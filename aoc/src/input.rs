@@ -0,0 +1,474 @@
+//! Line and record readers that don't build a `String` per line.
+//!
+//! Small files are read through a `BufReader` reusing a single line buffer, files of
+//! `MMAP_THRESHOLD` bytes or more are memory mapped (on unix) and sliced in place. A
+//! mapped file must not be truncated while it is read, the process gets a SIGBUS on the
+//! next access past the new end.
+
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::str::{self, FromStr};
+
+use crate::warn;
+
+pub const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+pub struct Input {
+    source: Source,
+    line_number: usize,
+}
+
+enum Source {
+    Buffered {
+        reader: BufReader<File>,
+        buffer: Vec<u8>,
+    },
+    Mapped {
+        map: MappedFile,
+        position: usize,
+    },
+}
+
+impl Input {
+    /// Opens `path`, memory mapping it when it is at least `MMAP_THRESHOLD` bytes. See
+    /// `mapped` for what that requires of the file.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Input> {
+        match File::open(&path)?.metadata()?.len() >= MMAP_THRESHOLD {
+            true => Input::mapped(path),
+            false => Input::buffered(path),
+        }
+    }
+
+    pub fn buffered<P: AsRef<Path>>(path: P) -> io::Result<Input> {
+        Ok(Input::new(Source::Buffered {
+            reader: BufReader::new(File::open(path)?),
+            buffer: Vec::new(),
+        }))
+    }
+
+    /// Memory maps `path`. Nothing may truncate the file until the `Input` is dropped,
+    /// reading past its new end raises SIGBUS and kills the process.
+    pub fn mapped<P: AsRef<Path>>(path: P) -> io::Result<Input> {
+        Ok(Input::new(Source::Mapped {
+            map: MappedFile::open(path)?,
+            position: 0,
+        }))
+    }
+
+    fn new(source: Source) -> Input {
+        Input {
+            source,
+            line_number: 0,
+        }
+    }
+
+    /// 1-based number of the line last returned by `next_line`.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Next line without its `\n` or `\r\n`, borrowed from the reader.
+    pub fn next_line(&mut self) -> Option<io::Result<&str>> {
+        let line = match &mut self.source {
+            Source::Buffered { reader, buffer } => {
+                buffer.clear();
+
+                match reader.read_until(b'\n', buffer) {
+                    Ok(0) => return None,
+                    Ok(_) => &buffer[..],
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+            Source::Mapped { map, position } => {
+                let bytes = &map.as_bytes()[*position..];
+
+                if bytes.is_empty() {
+                    return None;
+                }
+
+                let end = bytes
+                    .iter()
+                    .position(|&byte| byte == b'\n')
+                    .map(|index| index + 1)
+                    .unwrap_or(bytes.len());
+
+                *position += end;
+
+                &bytes[..end]
+            }
+        };
+
+        self.line_number += 1;
+
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        Some(
+            str::from_utf8(line).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+        )
+    }
+
+    /// Parses every line into `T`, lines that don't parse are logged and skipped.
+    pub fn records<T>(self) -> Records<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        Records {
+            input: self,
+            record: PhantomData,
+        }
+    }
+
+    /// Maps every line through `f`.
+    pub fn map_lines<T, F>(self, f: F) -> MapLines<F>
+    where
+        F: FnMut(&str) -> T,
+    {
+        MapLines { input: self, f }
+    }
+}
+
+/// Hands the values of `results` to `solve` as they are read, without collecting them.
+/// A read error ends the values early and is returned instead of `solve`'s answer.
+pub fn stream<T, R, I, F>(results: I, solve: F) -> io::Result<R>
+where
+    I: IntoIterator<Item = io::Result<T>>,
+    F: FnOnce(Values<'_, I::IntoIter>) -> R,
+{
+    let mut error = None;
+    let answer = solve(Values {
+        results: results.into_iter(),
+        error: &mut error,
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(answer),
+    }
+}
+
+/// The values `stream` hands out, see there.
+pub struct Values<'a, I> {
+    results: I,
+    error: &'a mut Option<io::Error>,
+}
+
+impl<'a, T, I> Iterator for Values<'a, I>
+where
+    I: Iterator<Item = io::Result<T>>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        match self.results.next()? {
+            Ok(value) => Some(value),
+            Err(error) => {
+                *self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+pub struct Records<T> {
+    input: Input,
+    record: PhantomData<T>,
+}
+
+impl<T> Records<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    /// Pairs every record with its 1-based line number.
+    pub fn numbered(self) -> Numbered<T> {
+        Numbered { records: self }
+    }
+
+    fn next_numbered(&mut self) -> Option<io::Result<(usize, T)>> {
+        loop {
+            let parsed = match self.input.next_line()? {
+                Ok(line) => line.parse::<T>(),
+                Err(error) => return Some(Err(error)),
+            };
+            let line_number = self.input.line_number();
+
+            match parsed {
+                Ok(record) => return Some(Ok((line_number, record))),
+                Err(error) => warn!("skipping line {}: {}", line_number, error),
+            }
+        }
+    }
+}
+
+impl<T> Iterator for Records<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_numbered()
+            .map(|record| record.map(|(_, record)| record))
+    }
+}
+
+pub struct Numbered<T> {
+    records: Records<T>,
+}
+
+impl<T> Iterator for Numbered<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Item = io::Result<(usize, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records.next_numbered()
+    }
+}
+
+pub struct MapLines<F> {
+    input: Input,
+    f: F,
+}
+
+impl<T, F> Iterator for MapLines<F>
+where
+    F: FnMut(&str) -> T,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let f = &mut self.f;
+
+        self.input.next_line().map(|line| line.map(&mut *f))
+    }
+}
+
+/// Read-only memory map of a whole file, unmapped on drop. Truncating the file while it
+/// is mapped makes reads past the new end raise SIGBUS.
+pub struct MappedFile {
+    #[cfg(unix)]
+    ptr: *mut std::os::raw::c_void,
+    #[cfg(unix)]
+    len: usize,
+    #[cfg(not(unix))]
+    bytes: Vec<u8>,
+}
+
+impl MappedFile {
+    #[cfg(unix)]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MappedFile> {
+        use std::os::unix::io::AsRawFd;
+
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let len = usize::try_from(len).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} bytes don't fit in the address space", len),
+            )
+        })?;
+
+        if len == 0 {
+            // mmap refuses empty mappings
+            return Ok(MappedFile {
+                ptr: std::ptr::null_mut(),
+                len,
+            });
+        }
+
+        // SAFETY: maps `len` bytes of an open file read-only and private, the result is
+        // checked against MAP_FAILED. The mapping stays valid after `file` is closed. It is
+        // only sound while the file keeps at least `len` bytes: MAP_PRIVATE doesn't copy
+        // pages that weren't written, so if another process truncates the file, reading
+        // the lost pages raises SIGBUS. Callers are told so on `Input::mapped`.
+        let ptr = unsafe {
+            sys::mmap(
+                std::ptr::null_mut(),
+                len,
+                sys::PROT_READ,
+                sys::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if ptr == sys::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(MappedFile { ptr, len })
+    }
+
+    #[cfg(not(unix))]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MappedFile> {
+        Ok(MappedFile {
+            bytes: std::fs::read(path)?,
+        })
+    }
+
+    #[cfg(unix)]
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.len {
+            0 => &[],
+            // SAFETY: `ptr` points to `len` readable bytes until `self` is dropped.
+            len => unsafe { std::slice::from_raw_parts(self.ptr as *const u8, len) },
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[cfg(unix)]
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: unmaps exactly the region mapped in `open`, nothing borrows it anymore.
+            unsafe {
+                sys::munmap(self.ptr, self.len);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::os::raw::{c_int, c_void};
+
+    /// `off_t` as `mmap` takes it, 32 bits on 32-bit Linux and 64 bits elsewhere.
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        target_pointer_width = "32"
+    ))]
+    #[allow(non_camel_case_types)]
+    pub type off_t = i32;
+    #[cfg(not(all(
+        any(target_os = "linux", target_os = "android"),
+        target_pointer_width = "32"
+    )))]
+    #[allow(non_camel_case_types)]
+    pub type off_t = i64;
+
+    pub const PROT_READ: c_int = 1;
+    pub const MAP_PRIVATE: c_int = 2;
+    pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+    extern "C" {
+        pub fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: off_t,
+        ) -> *mut c_void;
+        pub fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("aoc_input_{}_{}", std::process::id(), name));
+
+        fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn records() {
+        let path = write_temp("records", "1721\r\n979\nabc\n\n366");
+
+        for input in [
+            Input::buffered(&path).unwrap(),
+            Input::mapped(&path).unwrap(),
+        ] {
+            let numbers: Vec<u32> = input.records().collect::<io::Result<_>>().unwrap();
+
+            assert_eq!(numbers, vec![1721, 979, 366]);
+        }
+
+        let numbered: Vec<(usize, u32)> = Input::buffered(&path)
+            .unwrap()
+            .records()
+            .numbered()
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(numbered, vec![(1, 1721), (2, 979), (5, 366)]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn lines() {
+        let path = write_temp("lines", "FBFBBFFRLR\nBFFFBBFRRR\n");
+
+        for input in [
+            Input::buffered(&path).unwrap(),
+            Input::mapped(&path).unwrap(),
+        ] {
+            let lengths: Vec<usize> = input
+                .map_lines(|line| line.len())
+                .collect::<io::Result<_>>()
+                .unwrap();
+
+            assert_eq!(lengths, vec![10, 10]);
+        }
+
+        let mut input = Input::mapped(&path).unwrap();
+
+        assert_eq!(input.next_line().unwrap().unwrap(), "FBFBBFFRLR");
+        assert_eq!(input.line_number(), 1);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn streaming() {
+        let path = write_temp("streaming", "3\n4\n5\n");
+        let sum = stream(Input::open(&path).unwrap().records::<u32>(), |numbers| {
+            numbers.sum::<u32>()
+        });
+
+        assert_eq!(sum.unwrap(), 12);
+
+        let results = vec![Ok(1), Err(io::Error::other("broken")), Ok(2)];
+        let mut seen = Vec::new();
+
+        assert!(stream(results, |numbers| seen.extend(numbers)).is_err());
+        assert_eq!(seen, vec![1]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn empty_file() {
+        let path = write_temp("empty", "");
+
+        assert!(Input::mapped(&path).unwrap().next_line().is_none());
+        assert!(Input::buffered(&path).unwrap().next_line().is_none());
+
+        fs::remove_file(path).unwrap();
+    }
+}
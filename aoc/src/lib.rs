@@ -0,0 +1,3 @@
+pub mod input;
+pub mod lint;
+pub mod log;
@@ -0,0 +1,304 @@
+use std::error::Error;
+use std::fmt::Display;
+
+const PASS_ROWS: usize = 7;
+const PASS_COLUMNS: usize = 3;
+const OPCODES: &[&str] = &["acc", "jmp", "nop"];
+
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(line: usize, message: impl Into<String>) -> Violation {
+        Violation {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LintError {
+    UnknownDay(u8),
+}
+
+impl Display for LintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            LintError::UnknownDay(day) => write!(f, "No linter for day {}", day),
+        }
+    }
+}
+
+impl Error for LintError {}
+
+/// Checks `input` against the shape day `day` expects and returns every violation found.
+pub fn lint(day: u8, input: &str) -> Result<Vec<Violation>, LintError> {
+    let check: fn(&str) -> Result<(), String> = match day {
+        1 | 9 | 10 => check_number,
+        2 => check_password_rule,
+        3 => return Ok(lint_grid(input, &['.', '#'])),
+        5 => check_boarding_pass,
+        7 => check_bag_rule,
+        8 => check_instruction,
+        11 => return Ok(lint_grid(input, &['.', 'L', '#'])),
+        _ => return Err(LintError::UnknownDay(day)),
+    };
+
+    Ok(input
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| match line.is_empty() {
+            true => Some(Violation::new(index + 1, "empty line")),
+            false => check(line)
+                .err()
+                .map(|message| Violation::new(index + 1, message)),
+        })
+        .collect())
+}
+
+fn lint_grid(input: &str, cells: &[char]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut width = None;
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line_width = line.chars().count();
+
+        if let Some((column, c)) = line.chars().enumerate().find(|(_, c)| !cells.contains(c)) {
+            violations.push(Violation::new(
+                line_number,
+                format!("unexpected {:?} at column {}", c, column + 1),
+            ));
+        }
+
+        match width {
+            None => width = Some(line_width),
+            Some(width) if width != line_width => violations.push(Violation::new(
+                line_number,
+                format!("row is {} wide, expected {}", line_width, width),
+            )),
+            _ => (),
+        }
+    }
+
+    if width.is_none() {
+        violations.push(Violation::new(1, "empty grid"));
+    }
+
+    violations
+}
+
+fn check_number(line: &str) -> Result<(), String> {
+    if line.chars().all(|c| c.is_ascii_digit()) {
+        line.parse::<u64>()
+            .map(|_| ())
+            .map_err(|_| format!("{:?} is out of range", line))
+    } else {
+        Err(format!("expected a number, found {:?}", line))
+    }
+}
+
+/// `min-max c: password`
+fn check_password_rule(line: &str) -> Result<(), String> {
+    let (policy, password) = line
+        .split_once(": ")
+        .ok_or("missing \": \" between policy and password")?;
+    let (bounds, character) = policy
+        .split_once(' ')
+        .ok_or("missing space between range and character")?;
+    let (min, max) = bounds.split_once('-').ok_or("missing '-' in range")?;
+
+    let min: usize = min
+        .parse()
+        .map_err(|_| format!("invalid range start {:?}", min))?;
+    let max: usize = max
+        .parse()
+        .map_err(|_| format!("invalid range end {:?}", max))?;
+
+    if min > max {
+        return Err(format!("range start {} is greater than end {}", min, max));
+    }
+
+    if character.chars().count() != 1 {
+        return Err(format!(
+            "expected a single character, found {:?}",
+            character
+        ));
+    }
+
+    if password.is_empty() || password.contains(char::is_whitespace) {
+        return Err(format!("invalid password {:?}", password));
+    }
+
+    Ok(())
+}
+
+/// Ten characters: seven `F`/`B` followed by three `L`/`R`.
+fn check_boarding_pass(line: &str) -> Result<(), String> {
+    if line.chars().count() != PASS_ROWS + PASS_COLUMNS {
+        return Err(format!(
+            "expected {} characters, found {}",
+            PASS_ROWS + PASS_COLUMNS,
+            line.chars().count()
+        ));
+    }
+
+    for (index, c) in line.chars().enumerate() {
+        let valid = match index < PASS_ROWS {
+            true => matches!(c, 'F' | 'B'),
+            false => matches!(c, 'L' | 'R'),
+        };
+
+        if !valid {
+            return Err(format!("unexpected {:?} at column {}", c, index + 1));
+        }
+    }
+
+    Ok(())
+}
+
+/// `<adjective> <color> bags contain <n> <adjective> <color> bag(s), ... .`
+fn check_bag_rule(line: &str) -> Result<(), String> {
+    let (container, content) = line
+        .split_once(" bags contain ")
+        .ok_or("missing \" bags contain \"")?;
+
+    check_color(container)?;
+
+    let content = content
+        .strip_suffix('.')
+        .ok_or("sentence does not end with '.'")?;
+
+    if content == "no other bags" {
+        return Ok(());
+    }
+
+    for item in content.split(", ") {
+        let (count, rest) = item
+            .split_once(' ')
+            .ok_or_else(|| format!("invalid content {:?}", item))?;
+
+        let count: usize = count
+            .parse()
+            .map_err(|_| format!("invalid bag count {:?}", count))?;
+
+        let color = match count {
+            0 => return Err(format!("zero bags in {:?}", item)),
+            1 => rest.strip_suffix(" bag"),
+            _ => rest.strip_suffix(" bags"),
+        }
+        .ok_or_else(|| {
+            format!(
+                "expected {} to be followed by a color and \"bag(s)\"",
+                count
+            )
+        })?;
+
+        check_color(color)?;
+    }
+
+    Ok(())
+}
+
+fn check_color(color: &str) -> Result<(), String> {
+    let words: Vec<&str> = color.split(' ').collect();
+
+    if words.len() == 2
+        && words
+            .iter()
+            .all(|word| word.chars().all(char::is_lowercase))
+    {
+        Ok(())
+    } else {
+        Err(format!("expected a two word color, found {:?}", color))
+    }
+}
+
+/// `<opcode> <+/-argument>`
+fn check_instruction(line: &str) -> Result<(), String> {
+    let (opcode, argument) = line
+        .split_once(' ')
+        .ok_or("missing space between opcode and argument")?;
+
+    if !OPCODES.contains(&opcode) {
+        return Err(format!("unknown opcode {:?}", opcode));
+    }
+
+    match argument.strip_prefix(&['+', '-'][..]) {
+        Some(_) => argument
+            .parse::<i32>()
+            .map(|_| ())
+            .map_err(|_| format!("invalid argument {:?}", argument)),
+        None => Err(format!("argument {:?} has no sign", argument)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_files() {
+        let files = [
+            (1, "../day_01/numbers.txt"),
+            (2, "../day_02/example.txt"),
+            (3, "../day_03/example.txt"),
+            (5, "../day_05/example.txt"),
+            (7, "../day_07/example.txt"),
+            (7, "../day_07/example_02.txt"),
+            (8, "../day_08/example.txt"),
+            (9, "../day_09/example.txt"),
+            (10, "../day_10/example.txt"),
+            (11, "../day_11/example.txt"),
+        ];
+
+        for (day, file) in files.iter() {
+            let input = std::fs::read_to_string(file).unwrap();
+
+            assert_eq!(lint(*day, &input), Ok(vec![]), "{}", file);
+        }
+    }
+
+    #[test]
+    fn violations() {
+        assert_eq!(
+            lint(2, "1-3 a: abcde\n3-1 b cdefg\n2-9 c: ccccccccc\n").unwrap(),
+            vec![Violation::new(
+                2,
+                "missing \": \" between policy and password"
+            )]
+        );
+        assert_eq!(
+            lint(11, "L.L\nL#\n.LX\n").unwrap(),
+            vec![
+                Violation::new(2, "row is 2 wide, expected 3"),
+                Violation::new(3, "unexpected 'X' at column 3"),
+            ]
+        );
+        assert_eq!(
+            lint(8, "nop +0\nmov +1\njmp 4\n").unwrap(),
+            vec![
+                Violation::new(2, "unknown opcode \"mov\""),
+                Violation::new(3, "argument \"4\" has no sign"),
+            ]
+        );
+        assert_eq!(
+            lint(7, "light red bags contain 1 bright white bags.\n").unwrap(),
+            vec![Violation::new(
+                1,
+                "expected 1 to be followed by a color and \"bag(s)\""
+            )]
+        );
+        assert_eq!(lint(4, ""), Err(LintError::UnknownDay(4)));
+    }
+}
@@ -0,0 +1,195 @@
+//! Tiny leveled logger writing to stderr, so answers on stdout stay clean.
+//!
+//! The level defaults to `warn` and is read from the `AOC_LOG` environment variable
+//! (`off`, `error`, `warn`, `info`, `debug` or `trace`). Every `-v` flag on the command
+//! line raises it by one level, `-vv` and `-vvv` count as two and three.
+
+use std::env;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+const ENV_VAR: &str = "AOC_LOG";
+const DEFAULT_LEVEL: Level = Level::Warn;
+const LEVELS: [Level; 5] = [
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(DEFAULT_LEVEL as usize);
+static START: OnceLock<Instant> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match *self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseLevelError;
+
+impl Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid log level")
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(ParseLevelError),
+        }
+    }
+}
+
+/// Sets the level from `AOC_LOG` and the `-v` flags of the process.
+pub fn init() {
+    START.get_or_init(Instant::now);
+
+    let base = match env::var(ENV_VAR) {
+        Ok(value) if value.trim().eq_ignore_ascii_case("off") => None,
+        Ok(value) => match value.parse() {
+            Ok(level) => Some(level),
+            Err(_) => {
+                eprintln!("Ignoring invalid {}={:?}", ENV_VAR, value);
+                Some(DEFAULT_LEVEL)
+            }
+        },
+        Err(_) => Some(DEFAULT_LEVEL),
+    };
+
+    let verbosity: usize = env::args()
+        .skip(1)
+        .map(|arg| count_verbose_flags(&arg))
+        .sum();
+
+    set_max_level(raise(base, verbosity));
+}
+
+/// Sets the most verbose level that gets written, `None` turns logging off.
+pub fn set_max_level(level: Option<Level>) {
+    MAX_LEVEL.store(level.map(|l| l as usize).unwrap_or(0), Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as usize <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Writes a record, use the `error!` .. `trace!` macros instead of calling this directly.
+pub fn write(level: Level, target: &str, args: fmt::Arguments) {
+    let elapsed = START.get_or_init(Instant::now).elapsed();
+
+    eprintln!(
+        "[{:>4}.{:06}s {:<5} {}] {}",
+        elapsed.as_secs(),
+        elapsed.subsec_micros(),
+        level,
+        target,
+        args
+    );
+}
+
+fn count_verbose_flags(arg: &str) -> usize {
+    match arg.strip_prefix('-') {
+        Some(flags) if !flags.is_empty() && flags.chars().all(|c| c == 'v') => flags.len(),
+        _ => 0,
+    }
+}
+
+fn raise(base: Option<Level>, verbosity: usize) -> Option<Level> {
+    let index = base.map(|level| level as usize).unwrap_or(0) + verbosity;
+
+    match index {
+        0 => None,
+        _ => Some(LEVELS[index.min(LEVELS.len()) - 1]),
+    }
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity() {
+        assert_eq!(count_verbose_flags("-v"), 1);
+        assert_eq!(count_verbose_flags("-vvv"), 3);
+        assert_eq!(count_verbose_flags("--explain"), 0);
+        assert_eq!(count_verbose_flags("-"), 0);
+
+        assert_eq!(raise(Some(Level::Warn), 0), Some(Level::Warn));
+        assert_eq!(raise(Some(Level::Warn), 2), Some(Level::Debug));
+        assert_eq!(raise(Some(Level::Info), 5), Some(Level::Trace));
+        assert_eq!(raise(None, 0), None);
+        assert_eq!(raise(None, 1), Some(Level::Error));
+    }
+
+    #[test]
+    fn parse_level() {
+        assert_eq!("DEBUG".parse(), Ok(Level::Debug));
+        assert_eq!(" trace ".parse(), Ok(Level::Trace));
+        assert_eq!("loud".parse::<Level>(), Err(ParseLevelError));
+    }
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::{env, fs, process};
+
+use aoc::{info, lint, log};
+
+const USAGE: &str = "Usage: aoc lint <day> <file>";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
+    let args: Vec<String> = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("-v"))
+        .collect();
+
+    match args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>()[..] {
+        ["lint", day, file] => run_lint(parse_day(day)?, file),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+fn run_lint(day: u8, file: &str) -> Result<(), Box<dyn Error>> {
+    let input = fs::read_to_string(file)?;
+    let violations = lint::lint(day, &input)?;
+
+    info!(
+        "checked {} lines of {} against day {}",
+        input.lines().count(),
+        file,
+        day
+    );
+
+    for violation in violations.iter() {
+        println!("{}:{}", file, violation);
+    }
+
+    if !violations.is_empty() {
+        eprintln!("{} violation(s) found", violations.len());
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Accepts `3`, `03` and `day_03`.
+fn parse_day(day: &str) -> Result<u8, Box<dyn Error>> {
+    day.trim_start_matches("day_")
+        .parse()
+        .map_err(|_| format!("Invalid day {:?}\n{}", day, USAGE).into())
+}
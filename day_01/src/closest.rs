@@ -0,0 +1,233 @@
+use std::fmt::{self, Display};
+
+use crate::entry::Entry;
+use crate::k_sum::Reuse;
+
+/// Which side of the target a closest sum may fall on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bound {
+    /// Largest sum not above the target.
+    Below,
+    /// Smallest sum not below the target.
+    Above,
+    /// Smallest distance on either side.
+    Absolute,
+}
+
+/// How the bound reads before the target, e.g. "below 2020".
+impl Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Bound::Below => "below",
+            Bound::Above => "above",
+            Bound::Absolute => "closest to",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closest {
+    /// Ascending entry indices.
+    pub indices: Vec<usize>,
+    pub sum: i128,
+    pub distance: u128,
+}
+
+/// Combination of `k` entries whose sum is nearest to `target` within `bound`, an exact
+/// match has distance 0.
+///
+/// The outer levels walk the sorted entries, the innermost pair is found with two
+/// pointers moving inwards from both ends, so `k = 3` is O(n²).
+pub fn find_closest<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    reuse: Reuse,
+    bound: Bound,
+) -> Option<Closest> {
+    let numbers: Vec<i128> = numbers.iter().map(|number| number.wide()).collect();
+    let mut order: Vec<usize> = (0..numbers.len()).collect();
+
+    order.sort_unstable_by_key(|&index| numbers[index]);
+
+    let mut search = Search {
+        numbers: &numbers,
+        target: target.wide(),
+        reuse,
+        bound,
+        chosen: Vec::with_capacity(k),
+        best: None,
+    };
+
+    search.closest(&order, k, 0);
+
+    search.best.map(|mut best| {
+        best.indices.sort_unstable();
+        best
+    })
+}
+
+struct Search<'a> {
+    numbers: &'a [i128],
+    target: i128,
+    reuse: Reuse,
+    bound: Bound,
+    chosen: Vec<usize>,
+    best: Option<Closest>,
+}
+
+impl Search<'_> {
+    /// Out of range sums only happen for `i128` entries, they saturate.
+    fn add(&self, sum: i128, index: usize) -> i128 {
+        sum.saturating_add(self.numbers[index])
+    }
+
+    fn is_exact(&self) -> bool {
+        matches!(self.best, Some(Closest { distance: 0, .. }))
+    }
+
+    fn closest(&mut self, order: &[usize], k: usize, sum: i128) {
+        match k {
+            0 => self.consider(&[], sum),
+            1 => {
+                for &index in order.iter() {
+                    self.consider(&[index], self.add(sum, index));
+                }
+            }
+            2 => self.closest_pair(order, sum),
+            _ => {
+                for (position, &index) in order.iter().enumerate() {
+                    // an equal value was already tried at this level
+                    if position > 0 && self.numbers[order[position - 1]] == self.numbers[index] {
+                        continue;
+                    }
+
+                    let rest = match self.reuse {
+                        Reuse::Distinct => &order[position + 1..],
+                        Reuse::Allowed => &order[position..],
+                    };
+
+                    self.chosen.push(index);
+                    self.closest(rest, k - 1, self.add(sum, index));
+                    self.chosen.pop();
+
+                    if self.is_exact() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn closest_pair(&mut self, order: &[usize], sum: i128) {
+        if order.is_empty() {
+            return;
+        }
+
+        let (mut low, mut high) = (0, order.len() - 1);
+
+        while low < high || (low == high && self.reuse == Reuse::Allowed) {
+            let pair_sum = self.add(self.add(sum, order[low]), order[high]);
+
+            self.consider(&[order[low], order[high]], pair_sum);
+
+            if pair_sum < self.target {
+                low += 1;
+            } else if pair_sum > self.target && high > 0 {
+                high -= 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn consider(&mut self, last: &[usize], sum: i128) {
+        let accepted = match self.bound {
+            Bound::Below => sum <= self.target,
+            Bound::Above => sum >= self.target,
+            Bound::Absolute => true,
+        };
+        let distance = sum.abs_diff(self.target);
+
+        let improves = match &self.best {
+            Some(best) => distance < best.distance,
+            None => true,
+        };
+
+        if accepted && improves {
+            let mut indices = self.chosen.clone();
+
+            indices.extend_from_slice(last);
+
+            self.best = Some(Closest {
+                indices,
+                sum,
+                distance,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_sum() {
+        let numbers: Vec<u32> = vec![1030, 1000, 1019, 2];
+        let closest = |k, bound| find_closest(&numbers, k, 2020, Reuse::Distinct, bound);
+
+        assert_eq!(
+            closest(2, Bound::Below),
+            Some(Closest {
+                indices: vec![1, 2],
+                sum: 2019,
+                distance: 1
+            })
+        );
+        assert_eq!(
+            closest(2, Bound::Above),
+            Some(Closest {
+                indices: vec![0, 1],
+                sum: 2030,
+                distance: 10
+            })
+        );
+        assert_eq!(closest(3, Bound::Absolute).map(|c| c.sum), Some(2021));
+        assert_eq!(closest(4, Bound::Below), None);
+        assert_eq!(closest(5, Bound::Absolute), None);
+    }
+
+    #[test]
+    fn bound_names() {
+        assert_eq!(Bound::Below.to_string(), "below");
+        assert_eq!(Bound::Absolute.to_string(), "closest to");
+    }
+
+    #[test]
+    fn exact_and_reuse() {
+        let numbers: Vec<u32> = vec![1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(
+            find_closest(&numbers, 3, 2020, Reuse::Distinct, Bound::Absolute),
+            Some(Closest {
+                indices: vec![1, 2, 4],
+                sum: 2020,
+                distance: 0
+            })
+        );
+        assert_eq!(
+            find_closest(&[1000, 5], 2, 2020, Reuse::Allowed, Bound::Below).map(|c| c.indices),
+            Some(vec![0, 0])
+        );
+        assert_eq!(
+            find_closest(&[-7i64, 3, -1], 2, -20, Reuse::Distinct, Bound::Above).map(|c| c.sum),
+            Some(-8)
+        );
+        assert_eq!(
+            find_closest(&[i128::MAX, 5], 2, 0, Reuse::Distinct, Bound::Absolute)
+                .map(|c| c.distance),
+            Some(i128::MAX as u128)
+        );
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::entry::Entry;
+use crate::k_sum::{too_large, Reuse};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Combination<T> {
+    /// Ascending entry indices, repeated only with `Reuse::Allowed`.
+    pub indices: Vec<usize>,
+    /// The entry at each index.
+    pub values: Vec<T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    /// Lexicographic on the indices.
+    Index,
+    /// Lexicographic on the sorted values, ties broken on the indices.
+    Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dedup {
+    /// Every combination of entries, entries with equal values give separate combinations.
+    Entries,
+    /// One combination per multiset of values, using the first entries holding them.
+    Values,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub reuse: Reuse,
+    pub order: Order,
+    pub dedup: Dedup,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            reuse: Reuse::Distinct,
+            order: Order::Index,
+            dedup: Dedup::Entries,
+        }
+    }
+}
+
+/// Every combination of `k` entries summing to `target`, searched for one `next` at a
+/// time.
+pub fn combinations<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    options: Options,
+) -> Combinations<'_, T> {
+    let target = target.wide();
+    let goal = match options.order {
+        Order::Index => Goal::Entries {
+            target,
+            first_only: options.dedup == Dedup::Values,
+        },
+        Order::Value => Goal::Groups { target },
+    };
+
+    Combinations {
+        numbers,
+        groups: Groups::new(numbers),
+        options,
+        search: Search::new(k, goal, options.reuse),
+        expansion: None,
+    }
+}
+
+/// Combinations in the requested order, see `combinations`.
+pub struct Combinations<'a, T> {
+    numbers: &'a [T],
+    groups: Groups,
+    options: Options,
+    /// Entries in index order, multisets of values in value order.
+    search: Search,
+    /// In value order, the entries still to pick for the current multiset.
+    expansion: Option<Search>,
+}
+
+impl<'a, T: Entry> Iterator for Combinations<'a, T> {
+    type Item = Combination<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = match self.options.order {
+            Order::Index => self.search.next(&self.groups)?,
+            Order::Value => loop {
+                if let Some(expansion) = &mut self.expansion {
+                    match expansion.next(&self.groups) {
+                        Some(indices) => break indices,
+                        None => self.expansion = None,
+                    }
+                }
+
+                let multiset = multiset(&self.search.next(&self.groups)?);
+
+                match self.options.dedup {
+                    Dedup::Values => {
+                        break self.groups.first_entries(&multiset, self.options.reuse)
+                    }
+                    Dedup::Entries => {
+                        let k = multiset.iter().map(|&(_, used)| used).sum();
+                        let mut entries: Vec<usize> = multiset
+                            .iter()
+                            .flat_map(|&(group, _)| self.groups.entries[group].iter().copied())
+                            .collect();
+
+                        entries.sort_unstable();
+
+                        self.expansion = Some(Search::new(
+                            k,
+                            Goal::Expand { entries, multiset },
+                            self.options.reuse,
+                        ));
+                    }
+                }
+            },
+        };
+
+        Some(Combination {
+            values: indices.iter().map(|&index| self.numbers[index]).collect(),
+            indices,
+        })
+    }
+}
+
+/// Number of combinations `combinations` would yield, without building them. `None` when
+/// it doesn't fit in a `u64`.
+pub fn count_combinations<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    options: Options,
+) -> Option<u64> {
+    let groups = Groups::new(numbers);
+    let mut search = Search::new(
+        k,
+        Goal::Groups {
+            target: target.wide(),
+        },
+        options.reuse,
+    );
+    let mut count: u64 = 0;
+
+    while let Some(picked) = search.next(&groups) {
+        let ways = match options.dedup {
+            Dedup::Values => 1,
+            Dedup::Entries => {
+                multiset(&picked)
+                    .iter()
+                    .try_fold(1, |ways: u64, &(group, used)| {
+                        let available = groups.entries[group].len() as u64;
+                        let choices = match options.reuse {
+                            Reuse::Distinct => binomial(available, used as u64),
+                            Reuse::Allowed => binomial(available + used as u64 - 1, used as u64),
+                        };
+
+                        ways.checked_mul(choices?)
+                    })?
+            }
+        };
+
+        count = count.checked_add(ways)?;
+    }
+
+    Some(count)
+}
+
+/// Entries grouped by value, ascending.
+struct Groups {
+    values: Vec<i128>,
+    entries: Vec<Vec<usize>>,
+    /// The group of every entry.
+    group: Vec<usize>,
+    lookup: HashMap<i128, usize>,
+}
+
+/// How many entries of each group a combination uses, as `(group, count)`.
+type Multiset = [(usize, usize)];
+
+impl Groups {
+    fn new<T: Entry>(numbers: &[T]) -> Groups {
+        let mut order: Vec<usize> = (0..numbers.len()).collect();
+
+        order.sort_by_key(|&index| (numbers[index], index));
+
+        let mut groups = Groups {
+            values: Vec::new(),
+            entries: Vec::new(),
+            group: vec![0; numbers.len()],
+            lookup: HashMap::new(),
+        };
+
+        for index in order {
+            let value = numbers[index].wide();
+
+            if groups.values.last() != Some(&value) {
+                groups.lookup.insert(value, groups.values.len());
+                groups.values.push(value);
+                groups.entries.push(Vec::new());
+            }
+
+            groups.group[index] = groups.values.len() - 1;
+            groups.entries.last_mut().unwrap().push(index);
+        }
+
+        groups
+    }
+
+    fn value(&self, entry: usize) -> i128 {
+        self.values[self.group[entry]]
+    }
+
+    fn first_entries(&self, multiset: &Multiset, reuse: Reuse) -> Vec<usize> {
+        let mut indices: Vec<usize> = multiset
+            .iter()
+            .flat_map(|&(group, used)| {
+                let entries = &self.entries[group];
+
+                (0..used).map(move |n| match reuse {
+                    Reuse::Distinct => entries[n],
+                    Reuse::Allowed => entries[0],
+                })
+            })
+            .collect();
+
+        indices.sort_unstable();
+        indices
+    }
+}
+
+/// Picked groups, ascending, as `(group, count)` runs.
+fn multiset(picked: &[usize]) -> Vec<(usize, usize)> {
+    let mut multiset: Vec<(usize, usize)> = Vec::new();
+
+    for &group in picked {
+        match multiset.last_mut() {
+            Some((last, used)) if *last == group => *used += 1,
+            _ => multiset.push((group, 1)),
+        }
+    }
+
+    multiset
+}
+
+/// What a `Search` picks and when a pick is allowed.
+enum Goal {
+    /// Groups in ascending order whose values sum to `target`. Values are picked in
+    /// ascending order, the last one is looked up instead of searched for.
+    Groups { target: i128 },
+    /// Entries in ascending order whose values sum to `target`, with `first_only` just
+    /// the ones `Dedup::Values` keeps.
+    Entries { target: i128, first_only: bool },
+    /// Entries in ascending order taking exactly `multiset` from every group, all from
+    /// `entries`.
+    Expand {
+        entries: Vec<usize>,
+        multiset: Vec<(usize, usize)>,
+    },
+}
+
+/// Depth-first search picking `k` options, suspended after every full pick so the
+/// combinations come out one at a time.
+struct Search {
+    k: usize,
+    goal: Goal,
+    reuse: Reuse,
+    started: bool,
+    /// The options of every level picked from, with the next one to try.
+    levels: Vec<(Vec<usize>, usize)>,
+    picked: Vec<usize>,
+}
+
+impl Search {
+    fn new(k: usize, goal: Goal, reuse: Reuse) -> Search {
+        Search {
+            k,
+            goal,
+            reuse,
+            started: false,
+            levels: Vec::with_capacity(k),
+            picked: Vec::with_capacity(k),
+        }
+    }
+
+    fn next(&mut self, groups: &Groups) -> Option<Vec<usize>> {
+        if !self.started {
+            self.started = true;
+
+            if self.k == 0 {
+                let accepted = match self.goal {
+                    Goal::Groups { target } | Goal::Entries { target, .. } => target == 0,
+                    Goal::Expand { .. } => true,
+                };
+
+                return match accepted {
+                    true => Some(Vec::new()),
+                    false => None,
+                };
+            }
+
+            self.levels.push((self.options(groups), 0));
+        }
+
+        loop {
+            let depth = self.levels.len().checked_sub(1)?;
+            let (options, next) = self.levels.last_mut()?;
+            let option = options.get(*next).copied();
+
+            *next += 1;
+
+            match option {
+                None => {
+                    self.levels.pop();
+                }
+                Some(option) => {
+                    self.picked.truncate(depth);
+                    self.picked.push(option);
+
+                    if self.picked.len() == self.k {
+                        return Some(self.picked.clone());
+                    }
+
+                    self.levels.push((self.options(groups), 0));
+                }
+            }
+        }
+    }
+
+    /// How many of the picks so far are from `group`.
+    fn used(&self, groups: &Groups, group: usize) -> usize {
+        self.picked
+            .iter()
+            .filter(|&&picked| match self.goal {
+                Goal::Groups { .. } => picked == group,
+                _ => groups.group[picked] == group,
+            })
+            .count()
+    }
+
+    /// What may be picked after `picked`.
+    fn options(&self, groups: &Groups) -> Vec<usize> {
+        let left = self.k - self.picked.len();
+        // the first entry an entry search may pick, entries only repeat with reuse
+        let from = match (self.picked.last(), self.reuse) {
+            (None, _) => 0,
+            (Some(&last), Reuse::Distinct) => last + 1,
+            (Some(&last), Reuse::Allowed) => last,
+        };
+
+        match &self.goal {
+            Goal::Groups { target } => {
+                let remaining = self.picked.iter().fold(*target, |remaining, &group| {
+                    remaining - groups.values[group]
+                });
+                let from = self.picked.last().copied().unwrap_or(0);
+                let available = |group: usize| match self.reuse {
+                    Reuse::Distinct => self.used(groups, group) < groups.entries[group].len(),
+                    Reuse::Allowed => true,
+                };
+
+                if left == 1 {
+                    return match groups.lookup.get(&remaining) {
+                        Some(&group) if group >= from && available(group) => vec![group],
+                        _ => Vec::new(),
+                    };
+                }
+
+                (from..groups.values.len())
+                    // every remaining pick is at least this large
+                    .take_while(|&group| !too_large(groups.values[group], left, remaining))
+                    .filter(|&group| {
+                        remaining.checked_sub(groups.values[group]).is_some() && available(group)
+                    })
+                    .collect()
+            }
+            Goal::Entries { target, first_only } => {
+                let remaining = self
+                    .picked
+                    .iter()
+                    .fold(*target, |remaining, &entry| remaining - groups.value(entry));
+                let candidates: Vec<usize> = match left {
+                    1 => match groups.lookup.get(&remaining) {
+                        Some(&group) => groups.entries[group]
+                            .iter()
+                            .copied()
+                            .filter(|&entry| entry >= from)
+                            .collect(),
+                        None => Vec::new(),
+                    },
+                    _ => (from..groups.group.len())
+                        .filter(|&entry| remaining.checked_sub(groups.value(entry)).is_some())
+                        .collect(),
+                };
+
+                candidates
+                    .into_iter()
+                    .filter(|&entry| {
+                        let group = groups.group[entry];
+                        let first = match self.reuse {
+                            Reuse::Distinct => groups.entries[group].get(self.used(groups, group)),
+                            Reuse::Allowed => groups.entries[group].first(),
+                        };
+
+                        !first_only || first == Some(&entry)
+                    })
+                    .collect()
+            }
+            Goal::Expand { entries, multiset } => entries
+                .iter()
+                .copied()
+                .filter(|&entry| {
+                    let group = groups.group[entry];
+                    let needed = multiset
+                        .iter()
+                        .find(|&&(other, _)| other == group)
+                        .map_or(0, |&(_, used)| used);
+
+                    entry >= from && self.used(groups, group) < needed
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `n` choose `k`, `None` when it doesn't fit in a `u64`.
+fn binomial(n: u64, k: u64) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+
+    // every step is the next binomial, so the u128 product can't overflow while the
+    // result still fits
+    (0..k.min(n - k)).try_fold(1, |acc: u64, i| {
+        u64::try_from(acc as u128 * (n - i) as u128 / (i + 1) as u128).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indices(numbers: &[i32], k: usize, target: i32, options: Options) -> Vec<Vec<usize>> {
+        combinations(numbers, k, target, options)
+            .map(|combination| combination.indices)
+            .collect()
+    }
+
+    #[test]
+    fn every_combination() {
+        let numbers: Vec<i32> = vec![1721, 979, 366, 299, 675, 1456, 1010, 1010];
+        let options = Options::default();
+
+        assert_eq!(
+            indices(&numbers, 2, 2020, options),
+            vec![vec![0, 3], vec![6, 7]]
+        );
+        assert_eq!(
+            combinations(&numbers, 3, 2020, options).collect::<Vec<_>>(),
+            vec![Combination {
+                indices: vec![1, 2, 4],
+                values: vec![979, 366, 675]
+            }]
+        );
+        assert_eq!(count_combinations(&numbers, 2, 2020, options), Some(2));
+        assert_eq!(
+            indices(&[-1, 3, -2, 2, 0], 2, 1, options),
+            vec![vec![0, 3], vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn ordering_and_dedup() {
+        let numbers: Vec<i32> = vec![5, 1, 4, 1, 5, 0];
+        let options = Options::default();
+
+        assert_eq!(
+            indices(&numbers, 2, 6, options),
+            vec![vec![0, 1], vec![0, 3], vec![1, 4], vec![3, 4]]
+        );
+        assert_eq!(
+            indices(
+                &numbers,
+                2,
+                5,
+                Options {
+                    order: Order::Value,
+                    ..options
+                }
+            ),
+            vec![vec![0, 5], vec![4, 5], vec![1, 2], vec![2, 3]]
+        );
+        assert_eq!(
+            indices(
+                &numbers,
+                2,
+                6,
+                Options {
+                    dedup: Dedup::Values,
+                    ..options
+                }
+            ),
+            vec![vec![0, 1]]
+        );
+        assert_eq!(
+            indices(
+                &numbers,
+                2,
+                10,
+                Options {
+                    reuse: Reuse::Allowed,
+                    ..options
+                }
+            ),
+            vec![vec![0, 0], vec![0, 4], vec![4, 4]]
+        );
+
+        for &reuse in [Reuse::Distinct, Reuse::Allowed].iter() {
+            for &dedup in [Dedup::Entries, Dedup::Values].iter() {
+                let options = Options {
+                    reuse,
+                    dedup,
+                    ..options
+                };
+
+                assert_eq!(
+                    count_combinations(&numbers, 3, 10, options),
+                    Some(combinations(&numbers, 3, 10, options).count() as u64)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn overflowing_counts() {
+        assert_eq!(binomial(62, 31), Some(465428353255261088));
+        assert_eq!(binomial(68, 34), None);
+        assert_eq!(
+            count_combinations(&[0u32; 68], 34, 0, Options::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// Integer type the expense report can be read into.
+///
+/// Searches run on `i128` copies of the entries, which is exact for every type but
+/// `i128` itself, where combinations whose partial sums overflow are skipped.
+pub trait Entry: Copy + Ord + Hash + Debug + Display + FromStr + From<u16> {
+    const SIGNED: bool;
+    const ONE: Self;
+
+    fn wide(self) -> i128;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// Parses one line, negative values are only rejected by unsigned types.
+    fn parse_entry(line: &str) -> Result<Self, EntryError> {
+        if !Self::SIGNED && line.starts_with('-') {
+            return Err(EntryError::Negative(line.to_string()));
+        }
+
+        line.parse()
+            .map_err(|_| EntryError::Invalid(line.to_string()))
+    }
+}
+
+macro_rules! entry {
+    ($($type:ty => $signed:expr),*) => {
+        $(
+            impl Entry for $type {
+                const SIGNED: bool = $signed;
+                const ONE: Self = 1;
+
+                fn wide(self) -> i128 {
+                    self as i128
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$type>::checked_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+entry!(u32 => false, u64 => false, i32 => true, i64 => true, i128 => true);
+
+#[derive(Debug, PartialEq)]
+pub enum EntryError {
+    Negative(String),
+    Invalid(String),
+}
+
+impl Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntryError::Negative(line) => {
+                write!(f, "negative entry {:?} in an unsigned report", line)
+            }
+            EntryError::Invalid(line) => write!(f, "invalid entry {:?}", line),
+        }
+    }
+}
+
+/// Product of `values`, `None` when it doesn't fit in `T`.
+pub fn checked_product<T: Entry>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |product, value| product.checked_mul(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signedness() {
+        assert_eq!(i32::parse_entry("-5"), Ok(-5));
+        assert_eq!(
+            u64::parse_entry("-5"),
+            Err(EntryError::Negative("-5".to_string()))
+        );
+        assert_eq!(
+            u64::parse_entry("x"),
+            Err(EntryError::Invalid("x".to_string()))
+        );
+        assert_eq!(i128::parse_entry(&i128::MIN.to_string()), Ok(i128::MIN));
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(checked_product(vec![1721u32, 299]), Some(514579));
+        assert_eq!(checked_product(vec![1_000_000u32, 1_000_000]), None);
+        assert_eq!(
+            checked_product(vec![1_000_000u64, 1_000_000]),
+            Some(1_000_000_000_000)
+        );
+        assert_eq!(checked_product(Vec::<i64>::new()), Some(1));
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use crate::entry::Entry;
+
+/// Whether a single entry may be used more than once in a combination.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reuse {
+    /// Every entry is used at most once, two entries with the same value still combine.
+    Distinct,
+    /// An entry may pair with itself, e.g. a single 1010 sums to 2020 as 1010 + 1010.
+    Allowed,
+}
+
+/// Indices (ascending) of `k` entries summing to `target`, an index appears more than
+/// once only when `reuse` is `Reuse::Allowed`.
+///
+/// The entries are visited in sorted order so every level stops as soon as the remaining
+/// entries are too large, the innermost pair is found with a hash lookup. That makes
+/// `k = 2` O(n) and `k = 3` O(n²) after sorting.
+pub fn find_k_sum<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    reuse: Reuse,
+) -> Option<Vec<usize>> {
+    let numbers: Vec<i128> = numbers.iter().map(|number| number.wide()).collect();
+    let mut order: Vec<usize> = (0..numbers.len()).collect();
+
+    order.sort_unstable_by_key(|&index| numbers[index]);
+
+    let mut chosen = Vec::with_capacity(k);
+
+    if search(&numbers, &order, k, target.wide(), reuse, &mut chosen) {
+        chosen.sort_unstable();
+
+        Some(chosen)
+    } else {
+        None
+    }
+}
+
+/// Whether `k` entries of at least `value` always sum past `target`.
+pub fn too_large(value: i128, k: usize, target: i128) -> bool {
+    match value.checked_mul(k as i128) {
+        Some(smallest) => smallest > target,
+        None => value > 0,
+    }
+}
+
+fn search(
+    numbers: &[i128],
+    order: &[usize],
+    k: usize,
+    target: i128,
+    reuse: Reuse,
+    chosen: &mut Vec<usize>,
+) -> bool {
+    match k {
+        0 => target == 0,
+        1 => match order.iter().find(|&&index| numbers[index] == target) {
+            Some(&index) => {
+                chosen.push(index);
+                true
+            }
+            None => false,
+        },
+        2 => match find_pair(numbers, order, target, reuse) {
+            Some((a, b)) => {
+                chosen.push(a);
+                chosen.push(b);
+                true
+            }
+            None => false,
+        },
+        _ => {
+            for (position, &index) in order.iter().enumerate() {
+                let value = numbers[index];
+
+                if too_large(value, k, target) {
+                    break;
+                }
+
+                // an equal value was already tried at this level
+                if position > 0 && numbers[order[position - 1]] == value {
+                    continue;
+                }
+
+                let remaining = match target.checked_sub(value) {
+                    Some(remaining) => remaining,
+                    None => continue,
+                };
+
+                chosen.push(index);
+
+                // with reuse the same entry stays available for the next levels
+                let rest = match reuse {
+                    Reuse::Distinct => &order[position + 1..],
+                    Reuse::Allowed => &order[position..],
+                };
+
+                if search(numbers, rest, k - 1, remaining, reuse, chosen) {
+                    return true;
+                }
+
+                chosen.pop();
+            }
+
+            false
+        }
+    }
+}
+
+fn find_pair(
+    numbers: &[i128],
+    order: &[usize],
+    target: i128,
+    reuse: Reuse,
+) -> Option<(usize, usize)> {
+    let mut seen: HashMap<i128, usize> = HashMap::new();
+    let smallest = match order.first() {
+        Some(&index) => numbers[index],
+        None => return None,
+    };
+
+    for &index in order.iter() {
+        let value = numbers[index];
+
+        // even the smallest entry is too large a partner from here on
+        if smallest
+            .checked_add(value)
+            .map_or(value > 0, |sum| sum > target)
+        {
+            break;
+        }
+
+        let partner = match target.checked_sub(value) {
+            Some(partner) => partner,
+            None => continue,
+        };
+
+        if reuse == Reuse::Allowed && partner == value {
+            return Some((index, index));
+        }
+
+        // only entries seen before are looked up, so an entry never pairs with itself
+        if let Some(&other) = seen.get(&partner) {
+            return Some((other, index));
+        }
+
+        seen.insert(value, index);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_arity() {
+        let numbers: Vec<u32> = vec![1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(find_k_sum(&numbers, 1, 366, Reuse::Distinct), Some(vec![2]));
+        assert_eq!(
+            find_k_sum(&numbers, 2, 2020, Reuse::Distinct),
+            Some(vec![0, 3])
+        );
+        assert_eq!(
+            find_k_sum(&numbers, 3, 2020, Reuse::Distinct),
+            Some(vec![1, 2, 4])
+        );
+        assert_eq!(
+            find_k_sum(&numbers, 4, 366 + 299 + 675 + 1456, Reuse::Distinct),
+            Some(vec![2, 3, 4, 5])
+        );
+        assert_eq!(find_k_sum(&numbers, 0, 0, Reuse::Distinct), Some(vec![]));
+        assert_eq!(find_k_sum(&numbers, 2, 1, Reuse::Distinct), None);
+        assert_eq!(find_k_sum(&numbers, 7, 5496, Reuse::Distinct), None);
+    }
+
+    #[test]
+    fn distinct_indices() {
+        assert_eq!(find_k_sum(&[1010, 5], 2, 2020, Reuse::Distinct), None);
+        assert_eq!(
+            find_k_sum(&[1010, 5, 1010], 2, 2020, Reuse::Distinct),
+            Some(vec![0, 2])
+        );
+        assert_eq!(
+            find_k_sum(&[674, 673, 1, 673], 3, 2020, Reuse::Distinct),
+            Some(vec![0, 1, 3])
+        );
+        assert_eq!(find_k_sum(&[673, 674], 3, 2020, Reuse::Distinct), None);
+    }
+
+    #[test]
+    fn allow_reuse() {
+        assert_eq!(
+            find_k_sum(&[1010, 5], 2, 2020, Reuse::Allowed),
+            Some(vec![0, 0])
+        );
+        assert_eq!(
+            find_k_sum(&[673, 674], 3, 2020, Reuse::Allowed),
+            Some(vec![0, 0, 1])
+        );
+        assert_eq!(find_k_sum(&[5], 3, 15, Reuse::Allowed), Some(vec![0, 0, 0]));
+    }
+
+    #[test]
+    fn negative_and_wide() {
+        assert_eq!(
+            find_k_sum(&[-3i32, 9, -10, 2, 4], 3, -11, Reuse::Distinct),
+            Some(vec![0, 2, 3])
+        );
+        assert_eq!(
+            find_k_sum(&[-5i64, -4, -1], 2, -9, Reuse::Distinct),
+            Some(vec![0, 1])
+        );
+        assert_eq!(
+            find_k_sum(&[u64::MAX, 1, u64::MAX - 1], 2, u64::MAX, Reuse::Distinct),
+            Some(vec![1, 2])
+        );
+        assert_eq!(
+            find_k_sum(&[i128::MAX - 1, 1, 0], 3, i128::MAX, Reuse::Distinct),
+            Some(vec![0, 1, 2])
+        );
+    }
+}
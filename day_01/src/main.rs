@@ -1,27 +1,101 @@
+use std::env;
 use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io;
 use std::time::Instant;
 
-const TARGET_VALUE: u32 = 2020;
+use aoc::input::Input;
+use aoc::{debug, info, log, warn};
+
+use closest::{find_closest, Bound};
+use combinations::{combinations, count_combinations, Dedup, Options, Order};
+use entry::{checked_product, Entry, EntryError};
+use k_sum::{find_k_sum, Reuse};
+
+mod closest;
+mod combinations;
+mod entry;
+mod k_sum;
+
+const TARGET_VALUE: u16 = 2020;
+
+#[derive(Debug, PartialEq)]
+enum ProductError {
+    NoMatch,
+    /// The matching entries, whose product doesn't fit in the entry type.
+    Overflow(Vec<usize>),
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
+    let args: Vec<String> = env::args().collect();
+
+    // `--type=u32|u64|i32|i64|i128`, the report is read into u32 by default
+    match option(&args, "--type") {
+        None | Some("u32") => run::<u32>(&args),
+        Some("u64") => run::<u64>(&args),
+        Some("i32") => run::<i32>(&args),
+        Some("i64") => run::<i64>(&args),
+        Some("i128") => run::<i128>(&args),
+        Some(other) => Err(format!("Invalid --type {:?}", other).into()),
+    }
+}
+
+fn run<T: Entry>(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let explain = args.iter().any(|arg| arg == "--explain");
+    let reuse = match args.iter().any(|arg| arg == "--allow-reuse") {
+        true => Reuse::Allowed,
+        false => Reuse::Distinct,
+    };
+    let options = combination_options(args, reuse)?;
+    let closest = closest_bound(args)?;
+
     // Read numbers input file
-    let numbers = read_file("numbers.txt")?;
+    let numbers: Vec<T> = read_file("numbers.txt")?;
 
     let start = Instant::now();
 
-    println!("Answer 1: {:?}", part_01(&numbers));
+    let answer = part_01(&numbers, reuse);
+
+    println!("Answer 1: {:?}", answer);
     println!("Completed in {:?}", start.elapsed());
 
+    if let (Err(ProductError::NoMatch), Some(bound)) = (answer, closest) {
+        report_closest(&numbers, 2, reuse, bound);
+    }
+
+    if explain {
+        explain_entries(
+            &numbers,
+            find_k_sum(&numbers, 2, T::from(TARGET_VALUE), reuse),
+        );
+    }
+
+    list_combinations(args, &numbers, 2, options);
+
     let start = Instant::now();
 
-    println!("Answer 2: {:?}", part_02(&numbers));
+    let answer = part_02(&numbers, reuse);
+
+    println!("Answer 2: {:?}", answer);
     println!("Completed in {:?}", start.elapsed());
 
+    if let (Err(ProductError::NoMatch), Some(bound)) = (answer, closest) {
+        report_closest(&numbers, 3, reuse, bound);
+    }
+
+    if explain {
+        explain_entries(
+            &numbers,
+            find_k_sum(&numbers, 3, T::from(TARGET_VALUE), reuse),
+        );
+    }
+
+    list_combinations(args, &numbers, 3, options);
+
     /*
     // lot of people did it with itertools
-    let result: u32 = numbers
+    let result: T = numbers
         .combinations(3)
         .filter(|x| x[0] + x[1] + x[2] == TARGET_VALUE)
         .next()
@@ -33,42 +107,153 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn read_file(filename: &str) -> std::io::Result<Vec<u32>> {
-    let input = File::open(filename)?;
-    let reader = BufReader::new(input);
+/// Skips lines that aren't numbers, but fails on negative entries the type can't hold.
+fn read_file<T: Entry>(filename: &str) -> io::Result<Vec<T>> {
+    let mut numbers = Vec::new();
+
+    for (index, entry) in Input::open(filename)?.map_lines(T::parse_entry).enumerate() {
+        match entry? {
+            Ok(number) => numbers.push(number),
+            Err(error @ EntryError::Negative(_)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", index + 1, error),
+                ))
+            }
+            Err(error) => warn!("skipping line {}: {}", index + 1, error),
+        }
+    }
 
-    Ok(reader
-        .lines()
-        .filter_map(|line| line.ok().and_then(|line| line.parse().ok()))
-        .collect())
+    info!("parsed {} numbers from {}", numbers.len(), filename);
+
+    Ok(numbers)
 }
 
-fn part_01(numbers: &Vec<u32>) -> Result<u32, &str> {
-    for a in numbers.iter() {
-        for b in numbers.iter() {
-            if a + b == TARGET_VALUE {
-                // return first match
-                return Ok(a * b);
-            }
+fn part_01<T: Entry>(numbers: &[T], reuse: Reuse) -> Result<T, ProductError> {
+    find_product(numbers, 2, reuse)
+}
+
+fn part_02<T: Entry>(numbers: &[T], reuse: Reuse) -> Result<T, ProductError> {
+    find_product(numbers, 3, reuse)
+}
+
+/// Product of the `k` entries summing to `TARGET_VALUE`.
+fn find_product<T: Entry>(numbers: &[T], k: usize, reuse: Reuse) -> Result<T, ProductError> {
+    match find_k_sum(numbers, k, T::from(TARGET_VALUE), reuse) {
+        Some(indices) => {
+            debug!("entries {:?} sum to {}", indices, TARGET_VALUE);
+
+            checked_product(indices.iter().map(|&index| numbers[index]))
+                .ok_or(ProductError::Overflow(indices))
         }
+        None => Err(ProductError::NoMatch),
     }
+}
 
-    Err("Something went wrong")
+/// `--order=index|value` and `--dedup=entries|values`.
+fn combination_options(args: &[String], reuse: Reuse) -> Result<Options, String> {
+    let order = match option(args, "--order") {
+        None | Some("index") => Order::Index,
+        Some("value") => Order::Value,
+        Some(other) => return Err(format!("Invalid --order {:?}", other)),
+    };
+    let dedup = match option(args, "--dedup") {
+        None | Some("entries") => Dedup::Entries,
+        Some("values") => Dedup::Values,
+        Some(other) => return Err(format!("Invalid --dedup {:?}", other)),
+    };
+
+    Ok(Options {
+        reuse,
+        order,
+        dedup,
+    })
 }
 
-fn part_02(numbers: &Vec<u32>) -> Result<u32, &str> {
-    for a in numbers.iter() {
-        for b in numbers.iter() {
-            for c in numbers.iter() {
-                if a + b + c == TARGET_VALUE {
-                    // return first match
-                    return Ok(a * b * c);
-                }
-            }
+/// `--closest[=below|above|absolute]`, absolute when no side is given.
+fn closest_bound(args: &[String]) -> Result<Option<Bound>, String> {
+    if args.iter().any(|arg| arg == "--closest") {
+        return Ok(Some(Bound::Absolute));
+    }
+
+    match option(args, "--closest") {
+        None => Ok(None),
+        Some("below") => Ok(Some(Bound::Below)),
+        Some("above") => Ok(Some(Bound::Above)),
+        Some("absolute") => Ok(Some(Bound::Absolute)),
+        Some(other) => Err(format!("Invalid --closest {:?}", other)),
+    }
+}
+
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix(name)?.strip_prefix('='))
+}
+
+/// `--all` lists every combination of `k` entries summing to `TARGET_VALUE`, `--count`
+/// only counts them.
+fn list_combinations<T: Entry>(args: &[String], numbers: &[T], k: usize, options: Options) {
+    if args.iter().any(|arg| arg == "--all") {
+        for combination in combinations(numbers, k, T::from(TARGET_VALUE), options) {
+            let product = match checked_product(combination.values.iter().copied()) {
+                Some(product) => product.to_string(),
+                None => "overflows".to_string(),
+            };
+
+            println!(
+                "  {:?} {:?} product {}",
+                combination.indices, combination.values, product
+            );
+        }
+    }
+
+    if args.iter().any(|arg| arg == "--count") {
+        match count_combinations(numbers, k, T::from(TARGET_VALUE), options) {
+            Some(count) => println!("  {} combinations", count),
+            None => println!("  more combinations than fit in a u64"),
+        }
+    }
+}
+
+/// Fallback when no combination of `k` entries hits `TARGET_VALUE` exactly.
+fn report_closest<T: Entry>(numbers: &[T], k: usize, reuse: Reuse, bound: Bound) {
+    match find_closest(numbers, k, T::from(TARGET_VALUE), reuse, bound) {
+        Some(closest) => {
+            let values: Vec<String> = closest
+                .indices
+                .iter()
+                .map(|&i| numbers[i].to_string())
+                .collect();
+
+            println!(
+                "  closest: {:?} {} = {}, {} from {}",
+                closest.indices,
+                values.join(" + "),
+                closest.sum,
+                closest.distance,
+                TARGET_VALUE
+            );
         }
+        None => println!(
+            "  no combination of {} entries is {} {}",
+            k, bound, TARGET_VALUE
+        ),
     }
+}
+
+fn explain_entries<T: Entry>(numbers: &[T], indices: Option<Vec<usize>>) {
+    match indices {
+        Some(indices) => {
+            for index in indices.iter() {
+                println!("  entry #{}: {}", index, numbers[*index]);
+            }
+
+            let values: Vec<String> = indices.iter().map(|&i| numbers[i].to_string()).collect();
 
-    Err("Something went wrong")
+            println!("  {} = {}", values.join(" + "), TARGET_VALUE);
+        }
+        None => println!("  no entries sum to {}", TARGET_VALUE),
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +264,33 @@ mod tests {
     fn example() {
         let numbers: Vec<u32> = vec![1721, 979, 366, 299, 675, 1456];
 
-        assert_eq!(part_01(&numbers), Ok(514579));
+        assert_eq!(part_01(&numbers, Reuse::Distinct), Ok(514579));
+        assert_eq!(part_02(&numbers, Reuse::Distinct), Ok(241861950));
+    }
+
+    #[test]
+    fn single_entry_does_not_pair_with_itself() {
+        let numbers: Vec<u32> = vec![1010, 1721, 299];
+
+        assert_eq!(part_01(&numbers, Reuse::Distinct), Ok(514579));
+        assert_eq!(part_01(&numbers, Reuse::Allowed), Ok(1010 * 1010));
+    }
+
+    #[test]
+    fn signed_and_overflow() {
+        let numbers: Vec<i64> = vec![-5, 2025, 3];
+
+        assert_eq!(part_01(&numbers, Reuse::Distinct), Ok(-10125));
+
+        let numbers: Vec<i32> = vec![1_000_002_020, -1_000_000_000, 7];
+
+        assert_eq!(
+            part_01(&numbers, Reuse::Distinct),
+            Err(ProductError::Overflow(vec![0, 1]))
+        );
+        assert_eq!(
+            part_02(&numbers, Reuse::Distinct),
+            Err(ProductError::NoMatch)
+        );
     }
 }
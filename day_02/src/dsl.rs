@@ -0,0 +1,734 @@
+//! Text definitions of password policies, one `name: expression` per line.
+//!
+//! ```text
+//! # the two puzzle policies
+//! sled rental: count(c) in min..=max
+//! toboggan: len >= j and (at(i) == c xor at(j) == c)
+//! strong: len >= 12 and (has_digit or has_upper)
+//! ```
+//!
+//! `c` is the rule's required char, `min`/`i` and `max`/`j` its two numbers. Literal
+//! chars are quoted (`'x'`). `not` binds tightest, then `and`, `xor` and `or`. Counts,
+//! positions and lengths are in the unit the policies are parsed with.
+
+use std::fmt::{self, Display};
+
+use crate::grapheme::clusters;
+use crate::policy::{past_the_end, AllOf, AnyOf, Not, PasswordPolicy, Verdict, Xor};
+use crate::rule::Rule;
+use crate::unit::{show, Unit};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Literal(usize),
+    Min,
+    Max,
+}
+
+impl Number {
+    fn resolve(self, rule: &Rule) -> usize {
+        match self {
+            Number::Literal(number) => number,
+            Number::Min => rule.min_char,
+            Number::Max => rule.max_char,
+        }
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Literal(number) => write!(f, "{}", number),
+            Number::Min => f.write_str("min"),
+            Number::Max => f.write_str("max"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Letter {
+    Required,
+    /// One grapheme cluster.
+    Literal(String),
+}
+
+impl Letter {
+    fn resolve<'a>(&'a self, rule: &'a Rule) -> &'a str {
+        match self {
+            Letter::Required => &rule.required_char,
+            Letter::Literal(c) => c,
+        }
+    }
+}
+
+impl Display for Letter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Letter::Required => f.write_str("c"),
+            Letter::Literal(c) => write!(f, "'{}'", c),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    const SYMBOLS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn holds(self, left: usize, right: usize) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        Comparison::SYMBOLS
+            .iter()
+            .find(|(_, comparison)| *comparison == self)
+            .map(|(symbol, _)| *symbol)
+            .unwrap_or("?")
+    }
+}
+
+/// `count(letter) in min..=max`
+pub struct Count {
+    pub letter: Letter,
+    pub min: Number,
+    pub max: Number,
+    pub unit: Unit,
+}
+
+impl PasswordPolicy for Count {
+    fn name(&self) -> String {
+        format!("count({}) in {}..={}", self.letter, self.min, self.max)
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let letter = self.letter.resolve(rule);
+        let (min, max) = (self.min.resolve(rule), self.max.resolve(rule));
+        let count = self
+            .unit
+            .split(&rule.password)
+            .iter()
+            .filter(|&&unit| unit == letter.as_bytes())
+            .count();
+
+        Verdict::new(
+            count >= min && count <= max,
+            format!("found {} '{}', allowed {}-{}", count, letter, min, max),
+        )
+    }
+}
+
+/// `at(position) == letter`, positions are 1-based.
+pub struct At {
+    pub position: Number,
+    pub letter: Letter,
+    pub unit: Unit,
+}
+
+impl PasswordPolicy for At {
+    fn name(&self) -> String {
+        format!("at({}) == {}", self.position, self.letter)
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let letter = self.letter.resolve(rule);
+        let position = self.position.resolve(rule);
+        let units = self.unit.split(&rule.password);
+        let found = position.checked_sub(1).and_then(|index| units.get(index));
+
+        match found {
+            Some(&unit) if unit == letter.as_bytes() => {
+                Verdict::new(true, format!("position {} is '{}'", position, letter))
+            }
+            Some(&unit) => Verdict::new(
+                false,
+                format!(
+                    "position {} is '{}', not '{}'",
+                    position,
+                    show(unit),
+                    letter
+                ),
+            ),
+            None => Verdict::new(false, past_the_end(position, units.len(), self.unit)),
+        }
+    }
+}
+
+/// `len <comparison> number`
+pub struct Length {
+    pub comparison: Comparison,
+    pub number: Number,
+    pub unit: Unit,
+}
+
+impl PasswordPolicy for Length {
+    fn name(&self) -> String {
+        format!("len {} {}", self.comparison.symbol(), self.number)
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let length = self.unit.split(&rule.password).len();
+        let number = self.number.resolve(rule);
+
+        Verdict::new(
+            self.comparison.holds(length, number),
+            format!(
+                "length {}, need {} {}",
+                length,
+                self.comparison.symbol(),
+                number
+            ),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+    Digit,
+    Upper,
+    Lower,
+}
+
+/// `has_digit`, `has_upper` or `has_lower`
+pub struct Has(pub Class);
+
+impl PasswordPolicy for Has {
+    fn name(&self) -> String {
+        format!("has_{}", self.class_name())
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let is_class = match self.0 {
+            Class::Digit => char::is_numeric,
+            Class::Upper => char::is_uppercase,
+            Class::Lower => char::is_lowercase,
+        };
+
+        match rule.password.chars().find(|&c| is_class(c)) {
+            Some(c) => Verdict::new(true, format!("'{}' is {}", c, self.class_name())),
+            None => Verdict::new(false, format!("no {} char", self.class_name())),
+        }
+    }
+}
+
+impl Has {
+    fn class_name(&self) -> &'static str {
+        match self.0 {
+            Class::Digit => "digit",
+            Class::Upper => "upper",
+            Class::Lower => "lower",
+        }
+    }
+}
+
+/// A top level policy from a definitions file.
+pub struct Named {
+    pub name: String,
+    pub policy: Box<dyn PasswordPolicy>,
+}
+
+impl PasswordPolicy for Named {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        self.policy.check(rule)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParsePolicyError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParsePolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParsePolicyError {}
+
+/// Parses every `name: expression` line, blank lines and `#` comments are skipped.
+pub fn parse_policies(source: &str, unit: Unit) -> Result<Vec<Named>, ParsePolicyError> {
+    let mut policies = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let error = |column: usize, message: &str| ParsePolicyError {
+            line: index + 1,
+            column,
+            message: message.to_string(),
+        };
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (name, expression) = match line.split_once(':') {
+            Some((name, _)) if name.trim().is_empty() => {
+                return Err(error(1, "missing policy name"))
+            }
+            Some(parts) => parts,
+            None => return Err(error(1, "expected `name: expression`")),
+        };
+        let offset = name.chars().count() + 1;
+        let tokens =
+            tokenize(expression, offset).map_err(|(column, message)| error(column, &message))?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            unit,
+        };
+        let policy = parser
+            .expression()
+            .and_then(|policy| match parser.peek() {
+                Token::End => Ok(policy),
+                _ => Err(parser.unexpected("end of the expression")),
+            })
+            .map_err(|(column, message)| error(column, &message))?;
+
+        policies.push(Named {
+            name: name.trim().to_string(),
+            policy,
+        });
+    }
+
+    Ok(policies)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(usize),
+    Char(String),
+    Symbol(&'static str),
+    End,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{}`", ident),
+            Token::Number(number) => write!(f, "`{}`", number),
+            Token::Char(c) => write!(f, "'{}'", c),
+            Token::Symbol(symbol) => write!(f, "`{}`", symbol),
+            Token::End => f.write_str("end of line"),
+        }
+    }
+}
+
+/// Column and message of a syntax error.
+type SyntaxError = (usize, String);
+
+const SYMBOLS: [&str; 9] = ["..=", "==", "!=", "<=", ">=", "<", ">", "(", ")"];
+
+/// Tokens with their 1-based column, `offset` chars precede `expression` on its line.
+fn tokenize(expression: &str, offset: usize) -> Result<Vec<(Token, usize)>, SyntaxError> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let column = offset + index + 1;
+        let rest = &chars[index..];
+
+        if c.is_whitespace() {
+            index += 1;
+        } else if c.is_ascii_digit() {
+            let digits: String = chars[index..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            let number = digits
+                .parse()
+                .map_err(|_| (column, format!("number {} is too large", digits)))?;
+
+            tokens.push((Token::Number(number), column));
+            index += digits.len();
+        } else if c.is_alphabetic() || c == '_' {
+            let ident: String = chars[index..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .collect();
+
+            index += ident.chars().count();
+            tokens.push((Token::Ident(ident), column));
+        } else if c == '\'' {
+            let literal: String = chars[index + 1..]
+                .iter()
+                .take_while(|&&c| c != '\'')
+                .collect();
+            let closed = chars.get(index + 1 + literal.chars().count()) == Some(&'\'');
+
+            if !closed || clusters(&literal).len() != 1 {
+                return Err((column, "expected one quoted char like 'a'".to_string()));
+            }
+
+            index += literal.chars().count() + 2;
+            tokens.push((Token::Char(literal), column));
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| {
+            symbol
+                .chars()
+                .enumerate()
+                .all(|(position, c)| rest.get(position) == Some(&c))
+        }) {
+            tokens.push((Token::Symbol(symbol), column));
+            index += symbol.len();
+        } else {
+            return Err((column, format!("unexpected '{}'", c)));
+        }
+    }
+
+    tokens.push((Token::End, offset + chars.len() + 1));
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    unit: Unit,
+}
+
+type Parsed<T> = Result<T, SyntaxError>;
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn column(&self) -> usize {
+        self.tokens[self.position].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+
+        if token != Token::End {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn unexpected(&self, expected: &str) -> SyntaxError {
+        (
+            self.column(),
+            format!("expected {}, found {}", expected, self.peek()),
+        )
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident == keyword)
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Parsed<()> {
+        match self.is_keyword(keyword) {
+            true => {
+                self.next();
+                Ok(())
+            }
+            false => Err(self.unexpected(&format!("`{}`", keyword))),
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Parsed<()> {
+        match self.peek() {
+            Token::Symbol(found) if *found == symbol => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected(&format!("`{}`", symbol))),
+        }
+    }
+
+    fn expression(&mut self) -> Parsed<Box<dyn PasswordPolicy>> {
+        let mut policies = vec![self.xor()?];
+
+        while self.is_keyword("or") {
+            self.next();
+            policies.push(self.xor()?);
+        }
+
+        Ok(match policies.len() {
+            1 => policies.remove(0),
+            _ => Box::new(AnyOf(policies)),
+        })
+    }
+
+    fn xor(&mut self) -> Parsed<Box<dyn PasswordPolicy>> {
+        let mut policy = self.and()?;
+
+        while self.is_keyword("xor") {
+            self.next();
+            policy = Box::new(Xor(policy, self.and()?));
+        }
+
+        Ok(policy)
+    }
+
+    fn and(&mut self) -> Parsed<Box<dyn PasswordPolicy>> {
+        let mut policies = vec![self.unary()?];
+
+        while self.is_keyword("and") {
+            self.next();
+            policies.push(self.unary()?);
+        }
+
+        Ok(match policies.len() {
+            1 => policies.remove(0),
+            _ => Box::new(AllOf(policies)),
+        })
+    }
+
+    fn unary(&mut self) -> Parsed<Box<dyn PasswordPolicy>> {
+        if self.is_keyword("not") {
+            self.next();
+
+            return Ok(Box::new(Not(self.unary()?)));
+        }
+
+        if let Token::Symbol("(") = self.peek() {
+            self.next();
+
+            let policy = self.expression()?;
+
+            self.expect_symbol(")")?;
+
+            return Ok(policy);
+        }
+
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Parsed<Box<dyn PasswordPolicy>> {
+        let column = self.column();
+        let ident = match self.peek() {
+            Token::Ident(ident) => ident.clone(),
+            _ => return Err(self.unexpected("a policy")),
+        };
+
+        self.next();
+
+        match ident.as_str() {
+            "count" => {
+                self.expect_symbol("(")?;
+
+                let letter = self.letter()?;
+
+                self.expect_symbol(")")?;
+                self.expect_keyword("in")?;
+
+                let min = self.number()?;
+
+                self.expect_symbol("..=")?;
+
+                let max = self.number()?;
+
+                Ok(Box::new(Count {
+                    letter,
+                    min,
+                    max,
+                    unit: self.unit,
+                }))
+            }
+            "at" => {
+                self.expect_symbol("(")?;
+
+                let position_column = self.column();
+                let position = self.number()?;
+
+                if position == Number::Literal(0) {
+                    return Err((position_column, "positions start at 1".to_string()));
+                }
+
+                self.expect_symbol(")")?;
+                self.expect_symbol("==")?;
+
+                let letter = self.letter()?;
+
+                Ok(Box::new(At {
+                    position,
+                    letter,
+                    unit: self.unit,
+                }))
+            }
+            "len" => {
+                let comparison = match self.peek() {
+                    Token::Symbol(symbol) => Comparison::SYMBOLS
+                        .iter()
+                        .find(|(found, _)| found == symbol)
+                        .map(|(_, comparison)| *comparison),
+                    _ => None,
+                };
+                let comparison = comparison.ok_or_else(|| self.unexpected("a comparison"))?;
+
+                self.next();
+
+                let number = self.number()?;
+
+                Ok(Box::new(Length {
+                    comparison,
+                    number,
+                    unit: self.unit,
+                }))
+            }
+            "has_digit" => Ok(Box::new(Has(Class::Digit))),
+            "has_upper" => Ok(Box::new(Has(Class::Upper))),
+            "has_lower" => Ok(Box::new(Has(Class::Lower))),
+            _ => Err((column, format!("unknown policy `{}`", ident))),
+        }
+    }
+
+    fn number(&mut self) -> Parsed<Number> {
+        let number = match self.peek() {
+            Token::Number(number) => Number::Literal(*number),
+            Token::Ident(ident) if ident == "min" || ident == "i" => Number::Min,
+            Token::Ident(ident) if ident == "max" || ident == "j" => Number::Max,
+            _ => return Err(self.unexpected("a number, `min` or `max`")),
+        };
+
+        self.next();
+
+        Ok(number)
+    }
+
+    fn letter(&mut self) -> Parsed<Letter> {
+        let letter = match self.peek() {
+            Token::Char(c) => Letter::Literal(c.clone()),
+            Token::Ident(ident) if ident == "c" => Letter::Required,
+            _ => return Err(self.unexpected("`c` or a quoted char")),
+        };
+
+        self.next();
+
+        Ok(letter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::tests::{rules, validity, EXAMPLE};
+    use crate::policy::{SledRental, Toboggan};
+
+    /// The puzzle example and one long password.
+    const RULES: &[&str] = &[
+        "1-3 a: abcde",
+        "1-3 b: cdefg",
+        "2-9 c: ccccccccc",
+        "1-2 x: xP4ssw0rdLong",
+    ];
+
+    fn error(source: &str) -> (usize, usize) {
+        let error = parse_policies(source, Unit::CodePoint).err().unwrap();
+
+        (error.line, error.column)
+    }
+
+    #[test]
+    fn puzzle_policies() {
+        let policies = parse_policies(
+            "# the two puzzle policies\n\
+             \n\
+             sled rental: count(c) in min..=max\n\
+             toboggan: len >= j and (at(i) == c xor at(j) == c)\n",
+            Unit::CodePoint,
+        )
+        .unwrap();
+
+        assert_eq!(policies.len(), 2);
+        assert_eq!(policies[0].name(), "sled rental");
+        assert_eq!(
+            validity(&policies[0], RULES),
+            validity(&SledRental(Unit::CodePoint), RULES)
+        );
+        assert_eq!(
+            validity(&policies[1], RULES),
+            validity(&Toboggan(Unit::CodePoint), RULES)
+        );
+        assert_eq!(
+            policies[1].policy.name(),
+            "all of (len >= max, at(min) == c xor at(max) == c)"
+        );
+        assert_eq!(
+            policies[1].check(&rules(EXAMPLE)[2]).reason,
+            "position 2 is 'c'; position 9 is 'c'"
+        );
+        assert!(!policies[1].is_valid(&"1-4 a: abc".parse().unwrap()));
+    }
+
+    #[test]
+    fn corporate_policies() {
+        let policies = parse_policies(
+            "strong: len >= 12 and (has_digit or has_upper)\n\
+             weak: not has_digit and count('c') in 0..=1\n\
+             xs: at(1) == 'x' or len < 5",
+            Unit::CodePoint,
+        )
+        .unwrap();
+
+        assert_eq!(
+            validity(&policies[0], RULES),
+            vec![false, false, false, true]
+        );
+        assert_eq!(
+            validity(&policies[1], RULES),
+            vec![true, true, false, false]
+        );
+        assert_eq!(
+            validity(&policies[2], RULES),
+            vec![false, false, false, true]
+        );
+
+        assert_eq!(error("strong len >= 12"), (1, 1));
+        assert_eq!(error("\nstrong: len => 12"), (2, 13));
+        assert_eq!(error("a: count(c) in 1..3"), (1, 17));
+        assert_eq!(error("a: at(0) == c"), (1, 7));
+        assert_eq!(error("a: has_digit has_upper"), (1, 14));
+        assert_eq!(error("a: is_long"), (1, 4));
+        assert_eq!(error("a: at(1) == 'xy'"), (1, 13));
+
+        let accented = parse_policies("a: at(1) == 'e\u{301}'", Unit::Grapheme).unwrap();
+
+        assert!(accented[0].is_valid(&"1-2 x: e\u{301}x".parse().unwrap()));
+        assert_eq!(
+            parse_policies("a: (len > 1", Unit::CodePoint)
+                .err()
+                .unwrap()
+                .to_string(),
+            "line 1, column 12: expected `)`, found end of line"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Extended grapheme cluster boundaries (UAX #29) from an in-house property table.
+//!
+//! The table covers control chars, combining marks of the Latin, Greek, Cyrillic,
+//! Hebrew, Arabic and Devanagari blocks, variation selectors, emoji (modifiers, ZWJ
+//! sequences, flags, tags) and Hangul jamo, which is what shows up in passwords. Code
+//! points outside it are treated as `Other`, i.e. a cluster of their own.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Property {
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Pictographic,
+    Other,
+}
+
+use Property::*;
+
+/// Sorted, non-overlapping `(first, last, property)` ranges.
+const TABLE: [(u32, u32, Property); 75] = [
+    (0x0000, 0x0009, Control),
+    (0x000A, 0x000A, Lf),
+    (0x000B, 0x000C, Control),
+    (0x000D, 0x000D, Cr),
+    (0x000E, 0x001F, Control),
+    (0x007F, 0x009F, Control),
+    (0x00A9, 0x00A9, Pictographic),
+    (0x00AD, 0x00AD, Control),
+    (0x00AE, 0x00AE, Pictographic),
+    (0x0300, 0x036F, Extend),
+    (0x0483, 0x0489, Extend),
+    (0x0591, 0x05BD, Extend),
+    (0x05BF, 0x05BF, Extend),
+    (0x05C1, 0x05C2, Extend),
+    (0x05C4, 0x05C5, Extend),
+    (0x05C7, 0x05C7, Extend),
+    (0x0600, 0x0605, Prepend),
+    (0x0610, 0x061A, Extend),
+    (0x061C, 0x061C, Control),
+    (0x064B, 0x065F, Extend),
+    (0x0670, 0x0670, Extend),
+    (0x06D6, 0x06DC, Extend),
+    (0x06DD, 0x06DD, Prepend),
+    (0x06DF, 0x06E4, Extend),
+    (0x06E7, 0x06E8, Extend),
+    (0x06EA, 0x06ED, Extend),
+    (0x070F, 0x070F, Prepend),
+    (0x0900, 0x0902, Extend),
+    (0x0903, 0x0903, SpacingMark),
+    (0x093A, 0x093A, Extend),
+    (0x093B, 0x093B, SpacingMark),
+    (0x093C, 0x093C, Extend),
+    (0x093E, 0x0940, SpacingMark),
+    (0x0941, 0x0948, Extend),
+    (0x0949, 0x094C, SpacingMark),
+    (0x094D, 0x094D, Extend),
+    (0x094E, 0x094F, SpacingMark),
+    (0x0951, 0x0957, Extend),
+    (0x0962, 0x0963, Extend),
+    (0x1100, 0x115F, L),
+    (0x1160, 0x11A7, V),
+    (0x11A8, 0x11FF, T),
+    (0x1AB0, 0x1AFF, Extend),
+    (0x1DC0, 0x1DFF, Extend),
+    (0x200B, 0x200B, Control),
+    (0x200C, 0x200C, Extend),
+    (0x200D, 0x200D, Zwj),
+    (0x200E, 0x200F, Control),
+    (0x2028, 0x202E, Control),
+    (0x203C, 0x203C, Pictographic),
+    (0x2049, 0x2049, Pictographic),
+    (0x2060, 0x206F, Control),
+    (0x20D0, 0x20F0, Extend),
+    (0x2122, 0x2122, Pictographic),
+    (0x2139, 0x2139, Pictographic),
+    (0x2194, 0x21AA, Pictographic),
+    (0x231A, 0x23FF, Pictographic),
+    (0x25AA, 0x25FE, Pictographic),
+    (0x2600, 0x27BF, Pictographic),
+    (0x2934, 0x2935, Pictographic),
+    (0x2B05, 0x2B55, Pictographic),
+    (0x3030, 0x3030, Pictographic),
+    (0x303D, 0x303D, Pictographic),
+    (0x3297, 0x3299, Pictographic),
+    (0xA960, 0xA97C, L),
+    (0xAC00, 0xD7A3, Lv), // LV or LVT, see `property`
+    (0xD7B0, 0xD7C6, V),
+    (0xD7CB, 0xD7FB, T),
+    (0xFE00, 0xFE0F, Extend),
+    (0xFE20, 0xFE2F, Extend),
+    (0xFEFF, 0xFEFF, Control),
+    (0x1F1E6, 0x1F1FF, RegionalIndicator),
+    (0x1F3FB, 0x1F3FF, Extend),
+    (0xE0000, 0xE001F, Control),
+    (0xE0020, 0xE007F, Extend),
+];
+
+/// Emoji blocks, minus the regional indicators and skin tone modifiers above.
+const PICTOGRAPHIC: (u32, u32) = (0x1F000, 0x1FAFF);
+
+// `%` rather than `is_multiple_of`, which needs Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn property(c: char) -> Property {
+    let code = c as u32;
+    let found = TABLE.binary_search_by(|&(first, last, _)| {
+        if last < code {
+            std::cmp::Ordering::Less
+        } else if first > code {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    });
+
+    match found {
+        // precomposed syllables with a trailing consonant are LVT
+        Ok(index) if TABLE[index].2 == Lv && (code - 0xAC00) % 28 != 0 => Lvt,
+        Ok(index) => TABLE[index].2,
+        Err(_) if code >= PICTOGRAPHIC.0 && code <= PICTOGRAPHIC.1 => Pictographic,
+        Err(_) => Other,
+    }
+}
+
+/// Whether there is a cluster boundary between `before` and `after`. `joins_pictographic`
+/// says `before` is a ZWJ following `Pictographic Extend*`, `odd_regional` that an odd
+/// number of regional indicators ends at `before`.
+fn is_boundary(
+    before: Property,
+    after: Property,
+    joins_pictographic: bool,
+    odd_regional: bool,
+) -> bool {
+    match (before, after) {
+        (Cr, Lf) => false,
+        (Cr, _) | (Lf, _) | (Control, _) => true,
+        (_, Cr) | (_, Lf) | (_, Control) => true,
+        (L, L) | (L, V) | (L, Lv) | (L, Lvt) => false,
+        (Lv, V) | (Lv, T) | (V, V) | (V, T) => false,
+        (Lvt, T) | (T, T) => false,
+        (_, Extend) | (_, Zwj) | (_, SpacingMark) => false,
+        (Prepend, _) => false,
+        (Zwj, Pictographic) if joins_pictographic => false,
+        (RegionalIndicator, RegionalIndicator) if odd_regional => false,
+        _ => true,
+    }
+}
+
+/// The extended grapheme clusters of `s`, in order.
+pub fn clusters(s: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<Property> = None;
+    // the chars so far end in `Pictographic Extend*`, or in that followed by a ZWJ
+    let mut pictographic_run = false;
+    let mut joins_pictographic = false;
+    let mut regional_count = 0;
+
+    for (index, c) in s.char_indices() {
+        let current = property(c);
+
+        if let Some(before) = previous {
+            if is_boundary(before, current, joins_pictographic, regional_count % 2 == 1) {
+                clusters.push(&s[start..index]);
+                start = index;
+            }
+        }
+
+        joins_pictographic = current == Zwj && pictographic_run;
+        pictographic_run = match current {
+            Pictographic => true,
+            Extend => pictographic_run,
+            _ => false,
+        };
+        regional_count = match current {
+            RegionalIndicator => regional_count + 1,
+            _ => 0,
+        };
+        previous = Some(current);
+    }
+
+    if start < s.len() {
+        clusters.push(&s[start..]);
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(clusters("abc"), vec!["a", "b", "c"]);
+        assert_eq!(clusters("e\u{301}x"), vec!["e\u{301}", "x"]);
+        assert_eq!(clusters("a\r\nb"), vec!["a", "\r\n", "b"]);
+        assert_eq!(
+            clusters("\u{1112}\u{1161}\u{11AB}한"),
+            vec!["\u{1112}\u{1161}\u{11AB}", "한"]
+        );
+        assert_eq!(clusters("नमस्ते"), vec!["न", "म", "स्", "ते"]);
+        assert_eq!(clusters(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn emoji() {
+        assert_eq!(clusters("👍🏽!"), vec!["👍🏽", "!"]);
+        assert_eq!(clusters("👨‍👩‍👧x"), vec!["👨‍👩‍👧", "x"]);
+        assert_eq!(clusters("🇧🇪🇳🇱🇫"), vec!["🇧🇪", "🇳🇱", "🇫"]);
+        assert_eq!(clusters("❤\u{FE0F}"), vec!["❤\u{FE0F}"]);
+        assert_eq!(clusters("a\u{200D}b"), vec!["a\u{200D}", "b"]);
+    }
+}
@@ -1,108 +1,228 @@
+use std::borrow::Borrow;
+use std::env;
 use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
 use std::str::FromStr;
 use std::time::Instant;
 
+use aoc::input::{stream, Input, Records};
+use aoc::{info, log};
+
+use dsl::parse_policies;
+use policy::{AllOf, AnyOf, ExactlyNOf, Not, PasswordPolicy, SledRental, Toboggan};
+use repair::{repair_count, repair_positions, Repair};
+use report::{report, write_csv};
+use rule::Rule;
+use stats::stats;
+use unit::Unit;
+
+mod dsl;
+mod grapheme;
+mod policy;
+mod repair;
+mod report;
+mod rule;
+mod stats;
+mod unit;
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let rules = read_file("input.txt")?;
+    log::init();
+
+    let args: Vec<String> = env::args().collect();
+    let compare = args.iter().any(|arg| arg == "--compare");
+    let print_report = args.iter().any(|arg| arg == "--report");
+    let suggest_repairs = args.iter().any(|arg| arg == "--repair");
+    // `--stats` prints a text table, `--stats=json` a JSON object
+    let stats_format = args
+        .iter()
+        .find(|arg| arg.starts_with("--stats"))
+        .map(|arg| arg.trim_start_matches("--stats"));
+    let csv_path = args.iter().find_map(|arg| arg.strip_prefix("--csv="));
+    let policies_path = args.iter().find_map(|arg| arg.strip_prefix("--policies="));
+    // what positions and counts refer to, code points unless `--unit=` says otherwise
+    let unit = match args.iter().find_map(|arg| arg.strip_prefix("--unit=")) {
+        Some(name) => Unit::from_str(name)?,
+        None => Unit::CodePoint,
+    };
 
     let start = Instant::now();
 
-    println!("Answer 1: {:?}", part_01(&rules));
+    println!(
+        "Answer 1: {:?}",
+        stream(read_rules("input.txt")?, |rules| part_01(rules, unit))?
+    );
     println!("Completed in {:?}", start.elapsed());
 
     let start = Instant::now();
 
-    println!("Answer 2: {:?}", part_02(&rules));
+    println!(
+        "Answer 2: {:?}",
+        stream(read_rules("input.txt")?, |rules| part_02(rules, unit))?
+    );
     println!("Completed in {:?}", start.elapsed());
 
-    Ok(())
-}
+    // the answers stream the rules, everything else looks at them more than once
+    let more = compare
+        || suggest_repairs
+        || policies_path.is_some()
+        || stats_format.is_some()
+        || print_report
+        || csv_path.is_some();
 
-fn part_01(rules: &Vec<Rule>) -> usize {
-    rules.iter().filter(|rule| rule.is_valid_01()).count()
-}
+    if !more {
+        return Ok(());
+    }
 
-fn part_02(rules: &Vec<Rule>) -> usize {
-    rules.iter().filter(|rule| rule.is_valid_02()).count()
-}
+    let numbered = read_numbered("input.txt")?;
+    let rules = numbered.iter().map(|(_, rule)| rule);
 
-#[derive(Debug)]
-struct Rule {
-    min_char: usize,
-    max_char: usize,
-    required_char: char,
-    password: String,
-}
+    if compare {
+        compare_policies(rules.clone(), unit);
+    }
 
-impl Rule {
-    fn is_valid_01(&self) -> bool {
-        let required_char_count = self
-            .password
-            .chars()
-            .filter(|&c| c == self.required_char)
-            .count();
+    if suggest_repairs {
+        print_repairs(&numbered, unit);
+    }
 
-        required_char_count >= self.min_char && required_char_count <= self.max_char
+    // `--policies=<file>` replaces the puzzle policies with the file's definitions
+    let policies: Vec<Box<dyn PasswordPolicy>> = match policies_path {
+        Some(path) => {
+            let definitions = parse_policies(&fs::read_to_string(path)?, unit)?;
+
+            info!("loaded {} policies from {}", definitions.len(), path);
+
+            for policy in definitions.iter() {
+                println!(
+                    "  {}: {} valid",
+                    policy.name,
+                    count_valid(rules.clone(), policy)
+                );
+            }
+
+            definitions
+                .into_iter()
+                .map(|policy| Box::new(policy) as Box<dyn PasswordPolicy>)
+                .collect()
+        }
+        None => vec![Box::new(SledRental(unit)), Box::new(Toboggan(unit))],
+    };
+
+    let policies: Vec<&dyn PasswordPolicy> = policies.iter().map(|policy| &**policy).collect();
+
+    match stats_format {
+        Some("") => print!("{}", stats(rules.clone(), &policies, unit)),
+        Some("=json") => stats(rules.clone(), &policies, unit).write_json(io::stdout())?,
+        Some(format) => return Err(format!("Invalid stats format {:?}", format).into()),
+        None => (),
     }
 
-    fn is_valid_02(&self) -> bool {
-        let password_chars: Vec<char> = self.password.chars().collect();
-        let index_01 = self.min_char - 1;
-        let index_02 = self.max_char - 1;
-
-        let char_01 = match password_chars.get(index_01) {
-            Some(c) => c,
-            None => return false,
-        };
-        let char_02 = match password_chars.get(index_02) {
-            Some(c) => c,
-            None => return false,
-        };
-
-        (char_01 == &self.required_char && char_02 != &self.required_char)
-            || (char_01 != &self.required_char && char_02 == &self.required_char)
+    if print_report || csv_path.is_some() {
+        let reports = report(&numbered, &policies);
+
+        if print_report {
+            for password_report in reports.iter() {
+                print!("{}", password_report);
+            }
+        }
+
+        if let Some(path) = csv_path {
+            write_csv(&reports, BufWriter::new(File::create(path)?))?;
+            info!("wrote {} password reports to {}", reports.len(), path);
+        }
     }
+
+    Ok(())
 }
 
-impl FromStr for Rule {
-    type Err = ParseIntError;
+fn part_01<R: Borrow<Rule>>(rules: impl IntoIterator<Item = R>, unit: Unit) -> usize {
+    count_valid(rules, &SledRental(unit))
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let sub_strings: Vec<&str> = s.trim().split(':').collect();
-        let (prefix, password) = (sub_strings[0], sub_strings[1]);
-        let password: String = password.trim().to_string();
+fn part_02<R: Borrow<Rule>>(rules: impl IntoIterator<Item = R>, unit: Unit) -> usize {
+    count_valid(rules, &Toboggan(unit))
+}
 
-        let sub_strings: Vec<&str> = prefix.trim().split(' ').collect();
-        let (bounds, character) = (sub_strings[0], sub_strings[1]);
+fn count_valid<R: Borrow<Rule>>(
+    rules: impl IntoIterator<Item = R>,
+    policy: &dyn PasswordPolicy,
+) -> usize {
+    rules
+        .into_iter()
+        .filter(|rule| policy.is_valid(rule.borrow()))
+        .count()
+}
 
-        let required_char: char = character.trim().chars().collect::<Vec<char>>()[0];
+/// How the sled rental and toboggan policies overlap.
+fn compare_policies<'a>(rules: impl IntoIterator<Item = &'a Rule> + Clone, unit: Unit) {
+    let both = || -> Vec<Box<dyn PasswordPolicy>> {
+        vec![Box::new(SledRental(unit)), Box::new(Toboggan(unit))]
+    };
+
+    println!(
+        "  valid under both: {}",
+        count_valid(rules.clone(), &AllOf(both()))
+    );
+    println!(
+        "  valid under either: {}",
+        count_valid(rules.clone(), &AnyOf(both()))
+    );
+    println!(
+        "  valid under exactly one: {}",
+        count_valid(rules.clone(), &ExactlyNOf(1, both()))
+    );
+    println!(
+        "  valid under neither: {}",
+        count_valid(rules, &Not(Box::new(AnyOf(both()))))
+    );
+}
 
-        let sub_strings: Vec<&str> = bounds.trim().split('-').collect();
-        let (min, max) = (sub_strings[0], sub_strings[1]);
+/// The smallest fix for every password failing a puzzle policy.
+fn print_repairs(rules: &[(usize, Rule)], unit: Unit) {
+    for (line, rule) in rules.iter() {
+        let repairs = [
+            ("sled rental", repair_count(rule, unit)),
+            ("toboggan", repair_positions(rule, unit)),
+        ];
+
+        for (policy, repair) in repairs.iter() {
+            match repair {
+                Some(Repair { edits, .. }) if edits.is_empty() => (),
+                Some(Repair { edits, password }) => {
+                    let edits: Vec<String> = edits.iter().map(|edit| edit.to_string()).collect();
+
+                    println!(
+                        "  line {} {}: {} -> {} ({})",
+                        line,
+                        policy,
+                        rule.password,
+                        password,
+                        edits.join(", ")
+                    );
+                }
+                None => println!("  line {} {}: cannot be repaired", line, policy),
+            }
+        }
+    }
+}
 
-        let min_char: usize = min.parse()?;
-        let max_char: usize = max.parse()?;
+/// The rules read straight from `filename`, one at a time.
+fn read_rules(filename: &str) -> io::Result<Records<Rule>> {
+    info!("streaming rules from {}", filename);
 
-        Ok(Rule {
-            min_char,
-            max_char,
-            required_char,
-            password,
-        })
-    }
+    Ok(Input::open(filename)?.records())
 }
 
-fn read_file(filename: &str) -> std::io::Result<Vec<Rule>> {
-    let input = File::open(filename)?;
-    let reader = BufReader::new(input);
+/// Rules with the line they were read from.
+fn read_numbered(filename: &str) -> io::Result<Vec<(usize, Rule)>> {
+    let rules: Vec<(usize, Rule)> = Input::open(filename)?
+        .records()
+        .numbered()
+        .collect::<io::Result<_>>()?;
+
+    info!("parsed {} rules from {}", rules.len(), filename);
 
-    Ok(reader
-        .lines()
-        .filter_map(|line| line.ok().and_then(|line| line.parse().ok()))
-        .collect())
+    Ok(rules)
 }
 
 #[cfg(test)]
@@ -111,15 +231,21 @@ mod tests {
 
     #[test]
     fn example_01() {
-        let rules = read_file("example.txt").unwrap();
+        let rules = read_numbered("example.txt").unwrap();
 
-        assert_eq!(part_01(&rules), 2);
+        assert_eq!(
+            part_01(rules.iter().map(|(_, rule)| rule), Unit::CodePoint),
+            2
+        );
     }
 
     #[test]
     fn example_02() {
-        let rules = read_file("example.txt").unwrap();
+        let rules = read_numbered("example.txt").unwrap();
 
-        assert_eq!(part_02(&rules), 1);
+        assert_eq!(
+            part_02(rules.iter().map(|(_, rule)| rule), Unit::CodePoint),
+            1
+        );
     }
 }
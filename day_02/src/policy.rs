@@ -0,0 +1,331 @@
+use crate::rule::Rule;
+use crate::unit::Unit;
+
+/// Outcome of checking one password, with a human readable reason either way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub valid: bool,
+    pub reason: String,
+}
+
+impl Verdict {
+    pub fn new(valid: bool, reason: String) -> Verdict {
+        Verdict { valid, reason }
+    }
+}
+
+/// A corporate password policy, checked against a rule's parameters and password.
+pub trait PasswordPolicy {
+    fn name(&self) -> String;
+
+    fn check(&self, rule: &Rule) -> Verdict;
+
+    fn is_valid(&self, rule: &Rule) -> bool {
+        self.check(rule).valid
+    }
+}
+
+/// Sled rental: the required char occurs between `min_char` and `max_char` times.
+pub struct SledRental(pub Unit);
+
+impl PasswordPolicy for SledRental {
+    fn name(&self) -> String {
+        "sled rental".to_string()
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let required = rule.required_char.as_bytes();
+        let required_char_count = self
+            .0
+            .split(&rule.password)
+            .iter()
+            .filter(|&&unit| unit == required)
+            .count();
+
+        Verdict::new(
+            required_char_count >= rule.min_char && required_char_count <= rule.max_char,
+            format!(
+                "found {} '{}', allowed {}-{}",
+                required_char_count, rule.required_char, rule.min_char, rule.max_char
+            ),
+        )
+    }
+}
+
+/// Official Toboggan Corporate Policy: exactly one of the 1-based positions `min_char`
+/// and `max_char` holds the required char.
+pub struct Toboggan(pub Unit);
+
+impl PasswordPolicy for Toboggan {
+    fn name(&self) -> String {
+        "toboggan".to_string()
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let units = self.0.split(&rule.password);
+        let holds_required = |position: usize| {
+            position
+                .checked_sub(1)
+                .and_then(|index| units.get(index))
+                .map(|&unit| unit == rule.required_char.as_bytes())
+        };
+        let (position_01, position_02) = (rule.min_char, rule.max_char);
+        let required_char = &rule.required_char;
+
+        match (holds_required(position_01), holds_required(position_02)) {
+            (Some(true), Some(true)) => Verdict::new(
+                false,
+                format!(
+                    "both positions {} and {} are '{}'",
+                    position_01, position_02, required_char
+                ),
+            ),
+            (Some(false), Some(false)) => Verdict::new(
+                false,
+                format!(
+                    "neither position {} nor {} is '{}'",
+                    position_01, position_02, required_char
+                ),
+            ),
+            (Some(char_01), Some(_)) => {
+                let position = if char_01 { position_01 } else { position_02 };
+
+                Verdict::new(
+                    true,
+                    format!("only position {} is '{}'", position, required_char),
+                )
+            }
+            _ => Verdict::new(false, past_the_end(position_02, units.len(), self.0)),
+        }
+    }
+}
+
+pub fn past_the_end(position: usize, length: usize, unit: Unit) -> String {
+    format!(
+        "position {} is past the end of a {} {} password",
+        position,
+        length,
+        unit.name()
+    )
+}
+
+fn names(policies: &[Box<dyn PasswordPolicy>]) -> String {
+    let names: Vec<String> = policies.iter().map(|policy| policy.name()).collect();
+
+    names.join(", ")
+}
+
+fn check_all(policies: &[Box<dyn PasswordPolicy>], rule: &Rule) -> Vec<Verdict> {
+    policies.iter().map(|policy| policy.check(rule)).collect()
+}
+
+/// Reasons of the verdicts that decided the outcome `valid`.
+fn reasons(verdicts: &[Verdict], valid: bool) -> String {
+    let reasons: Vec<&str> = verdicts
+        .iter()
+        .filter(|verdict| verdict.valid == valid)
+        .map(|verdict| verdict.reason.as_str())
+        .collect();
+
+    reasons.join("; ")
+}
+
+pub struct AllOf(pub Vec<Box<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for AllOf {
+    fn name(&self) -> String {
+        format!("all of ({})", names(&self.0))
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let verdicts = check_all(&self.0, rule);
+        let valid = verdicts.iter().all(|verdict| verdict.valid);
+
+        Verdict::new(valid, reasons(&verdicts, valid))
+    }
+}
+
+pub struct AnyOf(pub Vec<Box<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for AnyOf {
+    fn name(&self) -> String {
+        format!("any of ({})", names(&self.0))
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let verdicts = check_all(&self.0, rule);
+        let valid = verdicts.iter().any(|verdict| verdict.valid);
+
+        Verdict::new(valid, reasons(&verdicts, valid))
+    }
+}
+
+pub struct Not(pub Box<dyn PasswordPolicy>);
+
+impl PasswordPolicy for Not {
+    fn name(&self) -> String {
+        format!("not {}", self.0.name())
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let verdict = self.0.check(rule);
+
+        Verdict::new(!verdict.valid, verdict.reason)
+    }
+}
+
+/// Valid when exactly one of the two policies is.
+pub struct Xor(pub Box<dyn PasswordPolicy>, pub Box<dyn PasswordPolicy>);
+
+impl PasswordPolicy for Xor {
+    fn name(&self) -> String {
+        format!("{} xor {}", self.0.name(), self.1.name())
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let (verdict_01, verdict_02) = (self.0.check(rule), self.1.check(rule));
+
+        Verdict::new(
+            verdict_01.valid != verdict_02.valid,
+            format!("{}; {}", verdict_01.reason, verdict_02.reason),
+        )
+    }
+}
+
+/// Valid when exactly `n` of the policies are.
+pub struct ExactlyNOf(pub usize, pub Vec<Box<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for ExactlyNOf {
+    fn name(&self) -> String {
+        format!("exactly {} of ({})", self.0, names(&self.1))
+    }
+
+    fn check(&self, rule: &Rule) -> Verdict {
+        let holding = check_all(&self.1, rule)
+            .iter()
+            .filter(|verdict| verdict.valid)
+            .count();
+
+        Verdict::new(
+            holding == self.0,
+            format!(
+                "{} of {} hold, need exactly {}",
+                holding,
+                self.1.len(),
+                self.0
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// The puzzle example.
+    pub(crate) const EXAMPLE: &[&str] = &["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"];
+
+    pub(crate) fn rules(lines: &[&str]) -> Vec<Rule> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    pub(crate) fn validity(policy: &dyn PasswordPolicy, lines: &[&str]) -> Vec<bool> {
+        rules(lines)
+            .iter()
+            .map(|rule| policy.is_valid(rule))
+            .collect()
+    }
+
+    fn reasons(policy: &dyn PasswordPolicy) -> Vec<String> {
+        rules(EXAMPLE)
+            .iter()
+            .map(|rule| policy.check(rule).reason)
+            .collect()
+    }
+
+    #[test]
+    fn policies() {
+        assert_eq!(
+            validity(&SledRental(Unit::CodePoint), EXAMPLE),
+            vec![true, false, true]
+        );
+        assert_eq!(
+            validity(&Toboggan(Unit::CodePoint), EXAMPLE),
+            vec![true, false, false]
+        );
+        assert_eq!(
+            reasons(&SledRental(Unit::CodePoint)),
+            vec![
+                "found 1 'a', allowed 1-3",
+                "found 0 'b', allowed 1-3",
+                "found 9 'c', allowed 2-9"
+            ]
+        );
+        assert_eq!(
+            reasons(&Toboggan(Unit::CodePoint)),
+            vec![
+                "only position 1 is 'a'",
+                "neither position 1 nor 3 is 'b'",
+                "both positions 2 and 9 are 'c'"
+            ]
+        );
+        assert_eq!(
+            Toboggan(Unit::CodePoint)
+                .check(&"2-12 c: ccc".parse().unwrap())
+                .reason,
+            "position 12 is past the end of a 3 code point password"
+        );
+    }
+
+    #[test]
+    fn units() {
+        let rule: Rule = "1-2 e\u{301}: xe\u{301}".parse().unwrap();
+        let both = |unit| {
+            (
+                SledRental(unit).is_valid(&rule),
+                Toboggan(unit).is_valid(&rule),
+            )
+        };
+
+        assert_eq!(both(Unit::Grapheme), (true, true));
+        assert_eq!(both(Unit::CodePoint), (false, false));
+        assert_eq!(both(Unit::Byte), (false, false));
+        assert_eq!(
+            Toboggan(Unit::Byte)
+                .check(&"4-9 a: aé".parse().unwrap())
+                .reason,
+            "position 9 is past the end of a 3 byte password"
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        let both = || -> Vec<Box<dyn PasswordPolicy>> {
+            vec![
+                Box::new(SledRental(Unit::CodePoint)),
+                Box::new(Toboggan(Unit::CodePoint)),
+            ]
+        };
+
+        assert_eq!(validity(&AllOf(both()), EXAMPLE), vec![true, false, false]);
+        assert_eq!(validity(&AnyOf(both()), EXAMPLE), vec![true, false, true]);
+        assert_eq!(
+            validity(&Not(Box::new(Toboggan(Unit::CodePoint))), EXAMPLE),
+            vec![false, true, true]
+        );
+        assert_eq!(
+            validity(&ExactlyNOf(1, both()), EXAMPLE),
+            vec![false, false, true]
+        );
+        assert_eq!(
+            validity(&ExactlyNOf(0, both()), EXAMPLE),
+            vec![false, true, false]
+        );
+        assert_eq!(validity(&AllOf(vec![]), EXAMPLE), vec![true, true, true]);
+        assert_eq!(reasons(&AllOf(both()))[2], "both positions 2 and 9 are 'c'");
+        assert_eq!(
+            ExactlyNOf(1, both()).name(),
+            "exactly 1 of (sled rental, toboggan)"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt::{self, Display};
+
+use crate::rule::Rule;
+use crate::unit::{show, Unit};
+
+/// One unit edit, positions are 1-based in the original password except for inserts,
+/// which are always appended and give the position they end up at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Substitute {
+        position: usize,
+        from: Vec<u8>,
+        to: Vec<u8>,
+    },
+    Insert {
+        position: usize,
+        unit: Vec<u8>,
+    },
+    Delete {
+        position: usize,
+        unit: Vec<u8>,
+    },
+}
+
+impl Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edit::Substitute { position, from, to } => write!(
+                f,
+                "substitute '{}' at {} with '{}'",
+                show(from),
+                position,
+                show(to)
+            ),
+            Edit::Insert { position, unit } => {
+                write!(f, "insert '{}' at {}", show(unit), position)
+            }
+            Edit::Delete { position, unit } => {
+                write!(f, "delete '{}' at {}", show(unit), position)
+            }
+        }
+    }
+}
+
+/// The fewest edits making a password valid, no edits when it already is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub edits: Vec<Edit>,
+    /// Byte edits can split a multi-byte char, those bytes show as U+FFFD.
+    pub password: String,
+}
+
+/// Sled rental policy: every edit changes the required char count by at most one, so
+/// substituting (or appending when nothing is left to substitute) the missing chars,
+/// or deleting the surplus ones, is minimal. `None` when chars are missing but the
+/// required char spans several units, no edit can add one then.
+pub fn repair_count(rule: &Rule, unit: Unit) -> Option<Repair> {
+    let units = unit.split(&rule.password);
+    let required = rule.required_char.as_bytes();
+    let count = units.iter().filter(|&&unit| unit == required).count();
+    let mut edits = Vec::new();
+
+    if count < rule.min_char {
+        if unit.split(&rule.required_char).len() != 1 {
+            return None;
+        }
+
+        let mut missing = rule.min_char - count;
+
+        for (index, &from) in units.iter().enumerate() {
+            if missing == 0 {
+                break;
+            }
+
+            if from != required {
+                edits.push(Edit::Substitute {
+                    position: index + 1,
+                    from: from.to_vec(),
+                    to: required.to_vec(),
+                });
+                missing -= 1;
+            }
+        }
+
+        for offset in 1..=missing {
+            edits.push(Edit::Insert {
+                position: units.len() + offset,
+                unit: required.to_vec(),
+            });
+        }
+    } else if count > rule.max_char {
+        let surplus = count - rule.max_char;
+        let positions: Vec<usize> = (0..units.len())
+            .filter(|&index| units[index] == required)
+            .collect();
+
+        for &index in positions[positions.len() - surplus..].iter() {
+            edits.push(Edit::Delete {
+                position: index + 1,
+                unit: required.to_vec(),
+            });
+        }
+    }
+
+    Some(apply(&units, edits))
+}
+
+/// Toboggan policy: a too short password needs `max_char - len` insertions at the least,
+/// appending them can always set up the positions as well. Otherwise one substitution
+/// does. `None` when both positions are the same or the required char spans several
+/// units, which can never be valid.
+pub fn repair_positions(rule: &Rule, unit: Unit) -> Option<Repair> {
+    let units = unit.split(&rule.password);
+    let required = rule.required_char.as_bytes();
+    let (position_01, position_02) = (rule.min_char, rule.max_char);
+
+    if position_01 == position_02 || unit.split(&rule.required_char).len() != 1 {
+        return None;
+    }
+
+    let filler = filler(&units, required);
+    let holds = |position: usize| units.get(position - 1) == Some(&required);
+    let mut edits = Vec::new();
+
+    if units.len() < position_02 {
+        // the appended position that gets the required char, if any
+        let target = match position_01 > units.len() {
+            true => Some(position_01),
+            false if holds(position_01) => None,
+            false => Some(position_02),
+        };
+
+        for position in units.len() + 1..=position_02 {
+            let unit = if Some(position) == target {
+                required
+            } else {
+                filler
+            };
+
+            edits.push(Edit::Insert {
+                position,
+                unit: unit.to_vec(),
+            });
+        }
+    } else {
+        match (holds(position_01), holds(position_02)) {
+            (true, true) => edits.push(Edit::Substitute {
+                position: position_02,
+                from: required.to_vec(),
+                to: filler.to_vec(),
+            }),
+            (false, false) => edits.push(Edit::Substitute {
+                position: position_01,
+                from: units[position_01 - 1].to_vec(),
+                to: required.to_vec(),
+            }),
+            _ => (),
+        }
+    }
+
+    Some(apply(&units, edits))
+}
+
+/// Unit used where anything but `required` will do, taken from the password if possible.
+fn filler<'a>(units: &[&'a [u8]], required: &[u8]) -> &'a [u8] {
+    units
+        .iter()
+        .copied()
+        .find(|&unit| unit != required)
+        .unwrap_or(if required == b"a" { b"b" } else { b"a" })
+}
+
+/// Applies the edits, inserts in order at the end.
+fn apply(units: &[&[u8]], edits: Vec<Edit>) -> Repair {
+    let mut repaired: Vec<Option<&[u8]>> = units.iter().copied().map(Some).collect();
+    let mut appended: Vec<&[u8]> = Vec::new();
+
+    for edit in edits.iter() {
+        match edit {
+            Edit::Substitute { position, to, .. } => repaired[position - 1] = Some(to),
+            Edit::Delete { position, .. } => repaired[position - 1] = None,
+            Edit::Insert { unit, .. } => appended.push(unit),
+        }
+    }
+
+    let bytes: Vec<u8> = repaired
+        .into_iter()
+        .flatten()
+        .chain(appended)
+        .flatten()
+        .copied()
+        .collect();
+
+    Repair {
+        password: String::from_utf8_lossy(&bytes).into_owned(),
+        edits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{PasswordPolicy, SledRental, Toboggan};
+
+    fn rule(line: &str) -> Rule {
+        line.parse().unwrap()
+    }
+
+    #[test]
+    fn count() {
+        let repaired = |line: &str| {
+            let mut rule = rule(line);
+            let repair = repair_count(&rule, Unit::CodePoint).unwrap();
+
+            rule.password = repair.password.clone();
+            assert!(
+                SledRental(Unit::CodePoint).is_valid(&rule),
+                "{} -> {}",
+                line,
+                rule.password
+            );
+
+            (repair.password, repair.edits.len())
+        };
+
+        assert_eq!(repaired("1-3 a: abcde"), ("abcde".to_string(), 0));
+        assert_eq!(repaired("1-3 b: cdefg"), ("bdefg".to_string(), 1));
+        assert_eq!(repaired("4-5 b: bcb"), ("bbbb".to_string(), 2));
+        assert_eq!(repaired("1-2 c: ccacc"), ("cca".to_string(), 2));
+        assert_eq!(
+            repair_count(&rule("2-3 x: x"), Unit::CodePoint)
+                .unwrap()
+                .edits,
+            vec![Edit::Insert {
+                position: 2,
+                unit: b"x".to_vec()
+            }]
+        );
+        assert_eq!(
+            repair_count(&rule("1-1 e\u{301}: xy"), Unit::Grapheme)
+                .unwrap()
+                .password,
+            "e\u{301}y"
+        );
+        assert_eq!(repair_count(&rule("1-1 é: xy"), Unit::Byte), None);
+    }
+
+    #[test]
+    fn positions() {
+        let repaired = |line: &str| {
+            let mut rule = rule(line);
+            let repair = repair_positions(&rule, Unit::CodePoint).unwrap();
+
+            rule.password = repair.password.clone();
+            assert!(
+                Toboggan(Unit::CodePoint).is_valid(&rule),
+                "{} -> {}",
+                line,
+                rule.password
+            );
+
+            (repair.password, repair.edits.len())
+        };
+
+        assert_eq!(repaired("1-3 a: abcde"), ("abcde".to_string(), 0));
+        assert_eq!(repaired("1-3 b: cdefg"), ("bdefg".to_string(), 1));
+        assert_eq!(repaired("2-9 c: ccccccccc"), ("cccccccca".to_string(), 1));
+        assert_eq!(repaired("2-5 c: xc"), ("xcxxx".to_string(), 3));
+        assert_eq!(repaired("2-5 c: xy"), ("xyxxc".to_string(), 3));
+        assert_eq!(repaired("3-4 c: x"), ("xxcx".to_string(), 3));
+        assert_eq!(repair_positions(&rule("2-2 c: xc"), Unit::CodePoint), None);
+        assert_eq!(
+            Edit::Substitute {
+                position: 9,
+                from: b"c".to_vec(),
+                to: b"a".to_vec()
+            }
+            .to_string(),
+            "substitute 'c' at 9 with 'a'"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::policy::{PasswordPolicy, Verdict};
+use crate::rule::Rule;
+
+/// How one password fared against every policy.
+pub struct PasswordReport {
+    pub line: usize,
+    pub password: String,
+    /// Policy name and verdict, in the order the policies were given.
+    pub verdicts: Vec<(String, Verdict)>,
+}
+
+pub fn report(rules: &[(usize, Rule)], policies: &[&dyn PasswordPolicy]) -> Vec<PasswordReport> {
+    rules
+        .iter()
+        .map(|(line, rule)| PasswordReport {
+            line: *line,
+            password: rule.password.clone(),
+            verdicts: policies
+                .iter()
+                .map(|policy| (policy.name(), policy.check(rule)))
+                .collect(),
+        })
+        .collect()
+}
+
+impl Display for PasswordReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "line {}: {}", self.line, self.password)?;
+
+        for (name, verdict) in self.verdicts.iter() {
+            let outcome = if verdict.valid { "passed" } else { "failed" };
+
+            writeln!(f, "  {}: {}, {}", name, outcome, verdict.reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// One row per password and policy: `line,password,policy,result,reason`.
+pub fn write_csv<W: Write>(reports: &[PasswordReport], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "line,password,policy,result,reason")?;
+
+    for report in reports.iter() {
+        for (name, verdict) in report.verdicts.iter() {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                report.line,
+                csv_field(&report.password),
+                csv_field(name),
+                if verdict.valid { "passed" } else { "failed" },
+                csv_field(&verdict.reason)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes fields containing a separator, quote or line break (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{SledRental, Toboggan};
+    use crate::unit::Unit;
+
+    #[test]
+    fn csv() {
+        let rules: Vec<(usize, Rule)> = vec![
+            (1, "1-3 b: cdefg".parse().unwrap()),
+            (3, "1-2 \": a\",b".parse().unwrap()),
+        ];
+        let policies: [&dyn PasswordPolicy; 2] =
+            [&SledRental(Unit::CodePoint), &Toboggan(Unit::CodePoint)];
+        let reports = report(&rules, &policies);
+        let mut csv = Vec::new();
+
+        write_csv(&reports, &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "line,password,policy,result,reason\n\
+             1,cdefg,sled rental,failed,\"found 0 'b', allowed 1-3\"\n\
+             1,cdefg,toboggan,failed,neither position 1 nor 3 is 'b'\n\
+             3,\"a\"\",b\",sled rental,passed,\"found 1 '\"\"', allowed 1-2\"\n\
+             3,\"a\"\",b\",toboggan,passed,\"only position 2 is '\"\"'\"\n"
+        );
+        assert_eq!(
+            reports[0].to_string(),
+            "line 1: cdefg\n  \
+             sled rental: failed, found 0 'b', allowed 1-3\n  \
+             toboggan: failed, neither position 1 nor 3 is 'b'\n"
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::grapheme::clusters;
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub min_char: usize,
+    pub max_char: usize,
+    /// One grapheme cluster, e.g. `e` followed by a combining accent.
+    pub required_char: String,
+    pub password: String,
+}
+
+/// Part of a `min-max c: password` line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Component {
+    Range,
+    Char,
+    Separator,
+    Password,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseRuleError {
+    pub component: Component,
+    /// 1-based char column the problem starts at.
+    pub column: usize,
+    pub reason: String,
+}
+
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let component = match self.component {
+            Component::Range => "range",
+            Component::Char => "char",
+            Component::Separator => "separator",
+            Component::Password => "password",
+        };
+
+        write!(
+            f,
+            "column {}: malformed {}: {}",
+            self.column, component, self.reason
+        )
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor {
+            line: s,
+            position: 0,
+        };
+
+        cursor.skip_whitespace();
+
+        let min_column = cursor.column();
+        let min_char = cursor.number()?;
+
+        cursor.expect('-', Component::Range, "expected '-' between min and max")?;
+
+        let max_char = cursor.number()?;
+
+        if min_char == 0 {
+            return Err(cursor.error_at(min_column, Component::Range, "positions start at 1"));
+        }
+
+        if min_char > max_char {
+            let reason = format!("min {} is above max {}", min_char, max_char);
+
+            return Err(cursor.error_at(min_column, Component::Range, &reason));
+        }
+
+        if cursor.skip_whitespace() == 0 {
+            return Err(cursor.error(Component::Separator, "expected a space after the range"));
+        }
+
+        let required_char = match (cursor.peek(), clusters(cursor.rest()).first()) {
+            (Some(c), Some(cluster)) if c != ':' && !c.is_whitespace() => cluster.to_string(),
+            _ => return Err(cursor.error(Component::Char, "expected the required char")),
+        };
+
+        cursor.position += required_char.len();
+        cursor.expect(
+            ':',
+            Component::Separator,
+            "expected ':' after the required char",
+        )?;
+        cursor.skip_whitespace();
+
+        let password = cursor.rest().trim_end();
+
+        if password.is_empty() {
+            return Err(cursor.error(Component::Password, "password is empty"));
+        }
+
+        if let Some(offset) = password.find(char::is_whitespace) {
+            cursor.position += offset;
+
+            return Err(cursor.error(Component::Password, "password contains whitespace"));
+        }
+
+        Ok(Rule {
+            min_char,
+            max_char,
+            required_char,
+            password: password.to_string(),
+        })
+    }
+}
+
+/// Byte position in a line, errors report it as a char column.
+struct Cursor<'a> {
+    line: &'a str,
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn rest(&self) -> &str {
+        &self.line[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.line[..self.position].chars().count() + 1
+    }
+
+    /// Number of whitespace chars skipped.
+    fn skip_whitespace(&mut self) -> usize {
+        let mut skipped = 0;
+
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+            skipped += 1;
+        }
+
+        skipped
+    }
+
+    fn number(&mut self) -> Result<usize, ParseRuleError> {
+        let column = self.column();
+        let rest = self.rest();
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        if digits == 0 {
+            return Err(self.error(Component::Range, "expected a number"));
+        }
+
+        let number = self.rest()[..digits]
+            .parse()
+            .map_err(|_| self.error_at(column, Component::Range, "number too large"))?;
+
+        self.position += digits;
+
+        Ok(number)
+    }
+
+    fn expect(
+        &mut self,
+        c: char,
+        component: Component,
+        reason: &str,
+    ) -> Result<(), ParseRuleError> {
+        match self.peek() {
+            Some(found) if found == c => {
+                self.bump();
+                Ok(())
+            }
+            _ => Err(self.error(component, reason)),
+        }
+    }
+
+    fn error(&self, component: Component, reason: &str) -> ParseRuleError {
+        self.error_at(self.column(), component, reason)
+    }
+
+    fn error_at(&self, column: usize, component: Component, reason: &str) -> ParseRuleError {
+        ParseRuleError {
+            component,
+            column,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(line: &str) -> (Component, usize) {
+        let error = line.parse::<Rule>().unwrap_err();
+
+        (error.component, error.column)
+    }
+
+    #[test]
+    fn parse() {
+        let rule: Rule = " 10-12 é:  cdéfg ".parse().unwrap();
+
+        assert_eq!((rule.min_char, rule.max_char), (10, 12));
+        assert_eq!(rule.required_char, "é");
+        assert_eq!(rule.password, "cdéfg");
+
+        let rule: Rule = "1-2 e\u{301}: e\u{301}x".parse().unwrap();
+
+        assert_eq!(rule.required_char, "e\u{301}");
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(error(""), (Component::Range, 1));
+        assert_eq!(error("1 a: abc"), (Component::Range, 2));
+        assert_eq!(error("1-x a: abc"), (Component::Range, 3));
+        assert_eq!(error("3-1 a: abc"), (Component::Range, 1));
+        assert_eq!(error("0-1 a: abc"), (Component::Range, 1));
+        assert_eq!(
+            error("1-99999999999999999999 a: abc"),
+            (Component::Range, 3)
+        );
+        assert_eq!(error("1-3a: abc"), (Component::Separator, 4));
+        assert_eq!(error("1-3 : abc"), (Component::Char, 5));
+        assert_eq!(error("1-3 ab: abc"), (Component::Separator, 6));
+        assert_eq!(error("1-3 a abc"), (Component::Separator, 6));
+        assert_eq!(error("1-3 a:"), (Component::Password, 7));
+        assert_eq!(error("1-3 é: ab c"), (Component::Password, 10));
+        assert_eq!(
+            "3-1 a: abc".parse::<Rule>().unwrap_err().to_string(),
+            "column 1: malformed range: min 3 is above max 1"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::policy::PasswordPolicy;
+use crate::rule::Rule;
+use crate::unit::Unit;
+
+/// Rule count and valid rules per policy for one required char.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharStats {
+    pub rules: usize,
+    pub valid: Vec<usize>,
+}
+
+/// Smallest, mean and largest of some per password measure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    fn of(values: &[f64]) -> Option<Summary> {
+        if values.is_empty() {
+            return None;
+        }
+
+        Some(Summary {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// Aggregates over a password database, `None` summaries when it is empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub rules: usize,
+    pub policies: Vec<String>,
+    pub unit: Unit,
+    pub required: BTreeMap<String, CharStats>,
+    /// Rules per `max_char - min_char`.
+    pub widths: BTreeMap<usize, usize>,
+    /// Passwords per length in `unit`s.
+    pub lengths: BTreeMap<usize, usize>,
+    /// Entropy estimate in bits, see `entropy`.
+    pub entropy: Option<Summary>,
+}
+
+pub fn stats<'a>(
+    rules: impl IntoIterator<Item = &'a Rule>,
+    policies: &[&dyn PasswordPolicy],
+    unit: Unit,
+) -> Stats {
+    let mut required = BTreeMap::new();
+    let mut widths = BTreeMap::new();
+    let mut lengths = BTreeMap::new();
+    let mut entropies = Vec::new();
+    let mut count = 0;
+
+    for rule in rules {
+        count += 1;
+
+        let length = unit.split(&rule.password).len();
+        let char_stats = required
+            .entry(rule.required_char.clone())
+            .or_insert_with(|| CharStats {
+                rules: 0,
+                valid: vec![0; policies.len()],
+            });
+
+        char_stats.rules += 1;
+
+        for (valid, policy) in char_stats.valid.iter_mut().zip(policies.iter()) {
+            if policy.is_valid(rule) {
+                *valid += 1;
+            }
+        }
+
+        *widths.entry(rule.max_char - rule.min_char).or_insert(0) += 1;
+        *lengths.entry(length).or_insert(0) += 1;
+        entropies.push(entropy(&rule.password, length));
+    }
+
+    Stats {
+        rules: count,
+        policies: policies.iter().map(|policy| policy.name()).collect(),
+        unit,
+        required,
+        widths,
+        lengths,
+        entropy: Summary::of(&entropies),
+    }
+}
+
+/// Brute force entropy of a password of `length` units: `length * log2(pool)`, with the
+/// pool the sizes of the char classes it uses added up.
+fn entropy(password: &str, length: usize) -> f64 {
+    let mut classes = [false; 5];
+
+    for c in password.chars() {
+        let class = match c {
+            'a'..='z' => 0,
+            'A'..='Z' => 1,
+            '0'..='9' => 2,
+            ' '..='~' => 3,
+            _ => 4,
+        };
+
+        classes[class] = true;
+    }
+
+    // lower, upper, digits, printable ASCII symbols and a guess for anything else
+    let pool: u32 = [26, 26, 10, 33, 100]
+        .iter()
+        .zip(classes.iter())
+        .filter(|(_, &used)| used)
+        .map(|(size, _)| size)
+        .sum();
+
+    match pool {
+        0 => 0.0,
+        _ => length as f64 * f64::from(pool).log2(),
+    }
+}
+
+fn percentage(part: usize, whole: usize) -> f64 {
+    100.0 * part as f64 / whole as f64
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} rules, lengths in {}s", self.rules, self.unit.name())?;
+
+        write!(f, "\n{:<8} {:>6}", "required", "rules")?;
+
+        for name in self.policies.iter() {
+            write!(f, " {:>width$}", name, width = name.len().max(7))?;
+        }
+
+        writeln!(f)?;
+
+        for (required, char_stats) in self.required.iter() {
+            write!(f, "{:<8} {:>6}", required, char_stats.rules)?;
+
+            for (name, &valid) in self.policies.iter().zip(char_stats.valid.iter()) {
+                let rate = format!("{:.1}%", percentage(valid, char_stats.rules));
+
+                write!(f, " {:>width$}", rate, width = name.len().max(7))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        for (title, distribution) in [("width", &self.widths), ("length", &self.lengths)].iter() {
+            writeln!(f, "\n{:<8} {:>6}", title, "rules")?;
+
+            for (value, count) in distribution.iter() {
+                writeln!(f, "{:<8} {:>6}", value, count)?;
+            }
+        }
+
+        if let Some(Summary { min, mean, max }) = self.entropy {
+            writeln!(
+                f,
+                "\nentropy  {:.1} min, {:.1} mean, {:.1} max bits",
+                min, mean, max
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Stats {
+    /// The same aggregates as one JSON object.
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let required: Vec<String> = self
+            .required
+            .iter()
+            .map(|(required, char_stats)| {
+                let valid: Vec<String> = self
+                    .policies
+                    .iter()
+                    .zip(char_stats.valid.iter())
+                    .map(|(name, valid)| {
+                        format!("{{\"name\":{},\"valid\":{}}}", json_string(name), valid)
+                    })
+                    .collect();
+
+                // an array, policy names from a definitions file needn't be unique
+                format!(
+                    "{}:{{\"rules\":{},\"valid\":[{}]}}",
+                    json_string(required),
+                    char_stats.rules,
+                    valid.join(",")
+                )
+            })
+            .collect();
+        let policies: Vec<String> = self.policies.iter().map(|name| json_string(name)).collect();
+        let entropy = match self.entropy {
+            Some(Summary { min, mean, max }) => format!(
+                "{{\"min\":{:.3},\"mean\":{:.3},\"max\":{:.3}}}",
+                min, mean, max
+            ),
+            None => "null".to_string(),
+        };
+
+        writeln!(
+            writer,
+            "{{\"rules\":{},\"unit\":{},\"policies\":[{}],\"required\":{{{}}},\
+             \"widths\":{},\"lengths\":{},\"entropy\":{}}}",
+            self.rules,
+            json_string(self.unit.name()),
+            policies.join(","),
+            required.join(","),
+            json_counts(&self.widths),
+            json_counts(&self.lengths),
+            entropy
+        )
+    }
+}
+
+/// Object keys have to be strings.
+fn json_counts(distribution: &BTreeMap<usize, usize>) -> String {
+    let counts: Vec<String> = distribution
+        .iter()
+        .map(|(value, count)| format!("\"{}\":{}", value, count))
+        .collect();
+
+    format!("{{{}}}", counts.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c < ' ' => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{SledRental, Toboggan};
+
+    fn example() -> Stats {
+        let rules: Vec<Rule> = [
+            "1-3 a: abcde",
+            "1-3 b: cdefg",
+            "2-9 c: ccccccccc",
+            "1-2 a: aB3!",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+        let policies: [&dyn PasswordPolicy; 2] =
+            [&SledRental(Unit::CodePoint), &Toboggan(Unit::CodePoint)];
+
+        stats(&rules, &policies, Unit::CodePoint)
+    }
+
+    #[test]
+    fn aggregates() {
+        let stats = example();
+
+        assert_eq!(
+            stats.required["a"],
+            CharStats {
+                rules: 2,
+                valid: vec![2, 2]
+            }
+        );
+        assert_eq!(
+            stats.widths.iter().collect::<Vec<_>>(),
+            vec![(&1, &1), (&2, &2), (&7, &1)]
+        );
+        assert_eq!(stats.lengths[&5], 2);
+        assert_eq!(entropy("abcde", 5), 5.0 * 26f64.log2());
+        assert_eq!(entropy("aB3!", 4), 4.0 * 95f64.log2());
+        assert_eq!(Summary::of(&[]), None);
+    }
+
+    #[test]
+    fn json() {
+        let mut json = Vec::new();
+
+        example().write_json(&mut json).unwrap();
+
+        let json = String::from_utf8(json).unwrap();
+
+        assert!(json.starts_with(
+            "{\"rules\":4,\"unit\":\"code point\",\"policies\":[\"sled rental\",\"toboggan\"],\
+             \"required\":{\"a\":{\"rules\":2,\"valid\":[{\"name\":\"sled rental\",\"valid\":2},\
+             {\"name\":\"toboggan\",\"valid\":2}]},"
+        ));
+        assert!(json.contains("\"widths\":{\"1\":1,\"2\":2,\"7\":1},"));
+        assert_eq!(json_string("a\"\\\u{1}"), "\"a\\\"\\\\\\u0001\"");
+    }
+}
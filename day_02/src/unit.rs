@@ -0,0 +1,73 @@
+use std::str::FromStr;
+
+use crate::grapheme::clusters;
+
+/// What positions and counts in a password refer to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Byte,
+    CodePoint,
+    /// Extended grapheme cluster, what a reader sees as one char.
+    Grapheme,
+}
+
+impl Unit {
+    /// `s` split into units. They are byte slices since a byte of a multi-byte char is
+    /// not a `str`, a required char spanning several units never equals one of them.
+    pub fn split(self, s: &str) -> Vec<&[u8]> {
+        match self {
+            Unit::Byte => s.as_bytes().chunks(1).collect(),
+            Unit::CodePoint => s
+                .char_indices()
+                .map(|(index, c)| &s.as_bytes()[index..index + c.len_utf8()])
+                .collect(),
+            Unit::Grapheme => clusters(s).into_iter().map(str::as_bytes).collect(),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Byte => "byte",
+            Unit::CodePoint => "code point",
+            Unit::Grapheme => "grapheme",
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "byte" => Ok(Unit::Byte),
+            "code-point" => Ok(Unit::CodePoint),
+            "grapheme" => Ok(Unit::Grapheme),
+            _ => Err(format!(
+                "Invalid unit {:?}, expected byte, code-point or grapheme",
+                s
+            )),
+        }
+    }
+}
+
+/// A unit as text, bytes that don't form a char on their own show as U+FFFD.
+pub fn show(unit: &[u8]) -> String {
+    String::from_utf8_lossy(unit).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units() {
+        let password = "e\u{301}👍🏽";
+        let count = |unit: Unit| unit.split(password).len();
+
+        assert_eq!(count(Unit::Byte), 11);
+        assert_eq!(count(Unit::CodePoint), 4);
+        assert_eq!(count(Unit::Grapheme), 2);
+        assert_eq!(Unit::Grapheme.split(password)[0], "e\u{301}".as_bytes());
+        assert_eq!("code-point".parse(), Ok(Unit::CodePoint));
+    }
+}
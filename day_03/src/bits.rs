@@ -0,0 +1,74 @@
+const WORD_BITS: usize = 64;
+
+/// One bit per cell, every row padded to whole words so it starts on a word boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let words_per_row = width.div_ceil(WORD_BITS);
+
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set(&mut self, (x, y): (usize, usize)) {
+        self.words[y * self.words_per_row + x / WORD_BITS] |= 1 << (x % WORD_BITS);
+    }
+
+    pub fn row(&self, y: usize) -> Row<'_> {
+        let first = y * self.words_per_row;
+
+        Row(&self.words[first..first + self.words_per_row])
+    }
+
+    /// Whether the bit at `(x, y)` is set, both within bounds.
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        self.row(y).contains(x)
+    }
+}
+
+/// The words of one row.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a>(&'a [u64]);
+
+impl Row<'_> {
+    pub fn contains(&self, x: usize) -> bool {
+        self.0[x / WORD_BITS] >> (x % WORD_BITS) & 1 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid() {
+        let mut grid = BitGrid::new(70, 3);
+
+        grid.set((0, 0));
+        grid.set((69, 1));
+        grid.set((64, 2));
+
+        assert_eq!(grid.words.len(), 6);
+        assert!(grid.contains((69, 1)) && grid.row(2).contains(64));
+        assert!(!grid.contains((69, 0)) && !grid.contains((0, 1)));
+    }
+}
@@ -0,0 +1,205 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::map::{Position, Slope, TreeMap, OPEN, TREE};
+
+/// Name and cost of chars not in a legend.
+const OTHER: (&str, i64) = ("other", 0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Terrain {
+    pub c: char,
+    pub name: String,
+    /// Negative for terrain that helps, like lifts.
+    pub cost: i64,
+}
+
+/// What every map char costs to pass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Legend(pub Vec<Terrain>);
+
+/// Trees cost one and anything else nothing, so the cost is the tree count.
+impl Default for Legend {
+    fn default() -> Self {
+        Legend(vec![
+            Terrain {
+                c: TREE,
+                name: "tree".to_string(),
+                cost: 1,
+            },
+            Terrain {
+                c: OPEN,
+                name: "open".to_string(),
+                cost: 0,
+            },
+        ])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseLegendError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ParseLegendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseLegendError {}
+
+/// One `<char> <name> <cost>` line per terrain, like `# tree 1` or `L lift -5`. Blank
+/// lines and lines starting with `//` are skipped.
+impl FromStr for Legend {
+    type Err = ParseLegendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut terrain: Vec<Terrain> = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let error = |message: String| ParseLegendError {
+                line: index + 1,
+                message,
+            };
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (c, name, cost) = match fields[..] {
+                [c, name, cost] if c.chars().count() == 1 => (c, name, cost),
+                _ => {
+                    return Err(error(format!(
+                        "expected `<char> <name> <cost>`, got {:?}",
+                        line
+                    )))
+                }
+            };
+            let c = c.chars().next().unwrap();
+            let cost = cost
+                .parse()
+                .map_err(|_| error(format!("invalid cost {:?}", cost)))?;
+
+            if terrain.iter().any(|terrain| terrain.c == c) {
+                return Err(error(format!("'{}' is already in the legend", c)));
+            }
+
+            terrain.push(Terrain {
+                c,
+                name: name.to_string(),
+                cost,
+            });
+        }
+
+        Ok(Legend(terrain))
+    }
+}
+
+/// Total cost along a path and how many cells of every terrain it crossed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Traversal {
+    pub cost: i64,
+    /// In legend order, then `other` if chars outside the legend were crossed.
+    pub counts: Vec<(String, usize)>,
+}
+
+impl Legend {
+    /// One pass from `start` along `slope`, see `TreeMap::path`.
+    pub fn traverse(&self, map: &TreeMap, start: Position, slope: Slope) -> Traversal {
+        let mut counts = vec![0; self.0.len() + 1];
+
+        for position in map.path(start, slope) {
+            let c = map.get(position);
+            let index = self.0.iter().position(|terrain| terrain.c == c);
+
+            counts[index.unwrap_or(self.0.len())] += 1;
+        }
+
+        let other = counts[self.0.len()];
+        let cost = self
+            .0
+            .iter()
+            .zip(counts.iter())
+            .map(|(terrain, &count)| terrain.cost * count as i64)
+            .sum::<i64>()
+            + OTHER.1 * other as i64;
+        let mut counts: Vec<(String, usize)> = self
+            .0
+            .iter()
+            .map(|terrain| terrain.name.clone())
+            .chain(Some(OTHER.0.to_string()))
+            .zip(counts)
+            .collect();
+
+        if other == 0 {
+            counts.pop();
+        }
+
+        Traversal { cost, counts }
+    }
+}
+
+impl Display for Traversal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts: Vec<String> = self
+            .counts
+            .iter()
+            .map(|(name, count)| format!("{}: {}", name, count))
+            .collect();
+
+        write!(f, "cost {} ({})", self.cost, counts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn costs() {
+        let map: TreeMap = "#.L\n^#.\nL^#\n.?.".parse().unwrap();
+        let legend: Legend = "// lifts help\n# tree 1\n. open 0\n^ rock 3\n\nL lift -5\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            legend.traverse(&map, (0, 0), (1, 1)).to_string(),
+            "cost 3 (tree: 3, open: 1, rock: 0, lift: 0)"
+        );
+        assert_eq!(
+            legend.traverse(&map, (2, 0), (-1, 1)).to_string(),
+            "cost -9 (tree: 1, open: 1, rock: 0, lift: 2)"
+        );
+        assert_eq!(
+            legend.traverse(&map, (1, 0), (-1, 1)).to_string(),
+            "cost 4 (tree: 1, open: 1, rock: 1, lift: 0, other: 1)"
+        );
+
+        for slope in [(1, 1), (2, 1), (-1, 1), (1, 2)] {
+            assert_eq!(
+                Legend::default().traverse(&map, (0, 0), slope).cost,
+                map.count_trees((0, 0), slope) as i64
+            );
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let error = |legend: &str| legend.parse::<Legend>().unwrap_err().to_string();
+
+        assert_eq!(
+            error("# tree 1\nrock 3"),
+            "line 2: expected `<char> <name> <cost>`, got \"rock 3\""
+        );
+        assert_eq!(error("^ rock three"), "line 1: invalid cost \"three\"");
+        assert_eq!(
+            error("# tree 1\n# bush 2"),
+            "line 2: '#' is already in the legend"
+        );
+    }
+}
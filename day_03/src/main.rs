@@ -1,12 +1,62 @@
+use std::env;
 use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter};
+use std::num::ParseIntError;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 use std::time::Instant;
 
-const INIT_POSITION: usize = 0;
-const TREE: char = '#';
+use aoc::{debug, info, log};
+
+use legend::Legend;
+use map::{Position, Slope, TreeMap};
+use render::render;
+use route::least_trees;
+use search::search;
+
+mod bits;
+mod legend;
+mod map;
+mod render;
+mod route;
+mod search;
+
+const INIT_POSITION: Position = (0, 0);
+const SLOPE: Slope = (3, 1);
+const SLOPES: [Slope; 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+const SEARCH_RIGHTS: RangeInclusive<isize> = 0..=10;
+const SEARCH_DOWNS: RangeInclusive<isize> = 1..=5;
+const LEAST_TREES_MAX_RIGHT: usize = 3;
+const PPM_SCALE: usize = 4;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
+    let args: Vec<String> = env::args().collect();
+    let explain = args.iter().any(|arg| arg == "--explain");
+    // `--slope=right,down` and `--start=x,y` count the trees along one more slope
+    let slope = args.iter().find_map(|arg| arg.strip_prefix("--slope="));
+    let start_position = args.iter().find_map(|arg| arg.strip_prefix("--start="));
+    // `--search[=rights,downs]` with inclusive ranges like `-3..=3,1..=2`
+    let search_bounds = args
+        .iter()
+        .find(|arg| arg.starts_with("--search"))
+        .map(|arg| arg.trim_start_matches("--search"));
+    // `--least-trees[=k]` finds the best path moving down one and right up to k a step
+    let least_trees_max_right = args
+        .iter()
+        .find(|arg| arg.starts_with("--least-trees"))
+        .map(|arg| arg.trim_start_matches("--least-trees"));
+    // `--render` draws the part one path, `--ppm=<file>` all part two paths as an image
+    let print_render = args.iter().any(|arg| arg == "--render");
+    let ppm_path = args.iter().find_map(|arg| arg.strip_prefix("--ppm="));
+    // `--legend[=<file>]` prices every part two slope, trees only without a file
+    let legend_path = args
+        .iter()
+        .find(|arg| arg.starts_with("--legend"))
+        .map(|arg| arg.trim_start_matches("--legend"));
+
     let map = read_file("map.txt")?;
 
     let start = Instant::now();
@@ -16,57 +66,199 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("Answer 1: {:?}", tree_count);
     println!("Completed in {:?}", start.elapsed());
 
+    if explain {
+        explain_slope(&map, SLOPE);
+    }
+
     let result = part_02(&map);
 
     println!("Answer 2: {:?}", result);
     println!("Completed in {:?}", start.elapsed());
 
+    if explain {
+        for slope in SLOPES.iter() {
+            explain_slope(&map, *slope);
+        }
+    }
+
+    if let Some(path) = legend_path {
+        let legend = match path.strip_prefix('=') {
+            Some(path) => fs::read_to_string(path)?.parse()?,
+            None => Legend::default(),
+        };
+
+        for &slope in SLOPES.iter() {
+            println!(
+                "  slope right {} down {}: {}",
+                slope.0,
+                slope.1,
+                legend.traverse(&map, INIT_POSITION, slope)
+            );
+        }
+    }
+
+    if print_render {
+        print!("{}", render(&map, INIT_POSITION, &[SLOPE]));
+    }
+
+    if let Some(path) = ppm_path {
+        render(&map, INIT_POSITION, &SLOPES)
+            .write_ppm(BufWriter::new(File::create(path)?), PPM_SCALE)?;
+        info!("rendered {} slopes to {}", SLOPES.len(), path);
+    }
+
+    if slope.is_some() || start_position.is_some() {
+        let slope = match slope {
+            Some(slope) => parse_pair(slope)?,
+            None => SLOPE,
+        };
+        let start = match start_position {
+            Some(start) => parse_pair(start)?,
+            None => INIT_POSITION,
+        };
+
+        println!(
+            "  slope right {} down {} from {:?}: {} trees",
+            slope.0,
+            slope.1,
+            start,
+            map.count_trees(start, slope)
+        );
+    }
+
+    if let Some(bounds) = search_bounds {
+        let (rights, downs) = match bounds.strip_prefix('=') {
+            Some(bounds) => {
+                parse_pair::<Bounds>(bounds).map(|(rights, downs)| (rights.0, downs.0))?
+            }
+            None => (SEARCH_RIGHTS, SEARCH_DOWNS),
+        };
+        let start = Instant::now();
+
+        match search(&map, INIT_POSITION, rights.clone(), downs.clone()) {
+            Some(extremes) => {
+                println!(
+                    "  fewest trees {}: {:?}",
+                    extremes.fewest.0, extremes.fewest.1
+                );
+                println!("  most trees {}: {:?}", extremes.most.0, extremes.most.1);
+            }
+            None => println!("  no slopes within {:?} and {:?}", rights, downs),
+        }
+
+        println!("Completed in {:?}", start.elapsed());
+    }
+
+    if let Some(max_right) = least_trees_max_right {
+        let max_right = match max_right.strip_prefix('=') {
+            Some(max_right) => max_right.parse()?,
+            None => LEAST_TREES_MAX_RIGHT,
+        };
+        let start = Instant::now();
+        let route = least_trees(&map, INIT_POSITION, max_right);
+        let moves: Vec<String> = route
+            .moves(map.width())
+            .iter()
+            .map(|right| right.to_string())
+            .collect();
+
+        println!(
+            "  least trees moving right at most {}: {}, moves {}",
+            max_right,
+            route.trees,
+            moves.join(" ")
+        );
+        println!("Completed in {:?}", start.elapsed());
+    }
+
     Ok(())
 }
 
-fn part_01(map: &Vec<Vec<char>>) -> u32 {
-    traverse_map(map, (3, 1))
+fn part_01(map: &TreeMap) -> u32 {
+    traverse_map(map, SLOPE)
 }
 
-fn part_02(map: &Vec<Vec<char>>) -> u32 {
-    let slopes: Vec<(usize, usize)> = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+fn part_02(map: &TreeMap) -> u32 {
     let mut result = 1;
 
-    for slope in slopes.iter() {
+    for slope in SLOPES.iter() {
         result *= traverse_map(map, *slope)
     }
 
     result
 }
 
-fn traverse_map(map: &Vec<Vec<char>>, (right, down): (usize, usize)) -> u32 {
-    let mut position = INIT_POSITION;
-    let mut tree_count = 0;
+fn traverse_map(map: &TreeMap, slope: Slope) -> u32 {
+    let tree_count = map.count_trees(INIT_POSITION, slope) as u32;
 
-    for line in map.iter().step_by(down) {
-        if line[position] == TREE {
-            tree_count += 1
-        }
+    debug!(
+        "slope right {} down {} hits {} trees",
+        slope.0, slope.1, tree_count
+    );
 
-        position += right;
+    tree_count
+}
 
-        if position >= line.len() {
-            position -= line.len();
-        }
-    }
+fn explain_slope(map: &TreeMap, slope: Slope) {
+    let hits: Vec<String> = map
+        .tree_hits(INIT_POSITION, slope)
+        .iter()
+        .map(|(x, y)| format!("({}, {})", x, y))
+        .collect();
 
-    tree_count
+    println!(
+        "  slope right {} down {}: {} trees at {}",
+        slope.0,
+        slope.1,
+        hits.len(),
+        hits.join(" ")
+    );
 }
 
-fn read_file(filename: &str) -> std::io::Result<Vec<Vec<char>>> {
+fn read_file(filename: &str) -> Result<TreeMap, Box<dyn Error>> {
     let input = File::open(filename)?;
     let reader = BufReader::new(input);
 
-    Ok(reader
+    let rows: Vec<Vec<char>> = reader
         .lines()
         .filter_map(|line| line.ok().and_then(|line| line.parse::<String>().ok()))
         .map(|map_line| map_line.chars().collect())
-        .collect())
+        .collect();
+    let map = TreeMap::new(rows)?;
+
+    info!(
+        "parsed {} rows of width {} from {}",
+        map.height(),
+        map.width(),
+        filename
+    );
+
+    Ok(map)
+}
+
+/// An inclusive range like `-3..=3`, or a single value.
+struct Bounds(RangeInclusive<isize>);
+
+impl FromStr for Bounds {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once("..=") {
+            Some((low, high)) => Ok(Bounds(low.trim().parse()?..=high.trim().parse()?)),
+            None => s.trim().parse().map(|value| Bounds(value..=value)),
+        }
+    }
+}
+
+/// `a,b` as a pair, for slopes and positions.
+fn parse_pair<T: FromStr>(pair: &str) -> Result<(T, T), Box<dyn Error>>
+where
+    T::Err: Error + 'static,
+{
+    match pair.split_once(',') {
+        Some((a, b)) => Ok((a.trim().parse()?, b.trim().parse()?)),
+        None => Err(format!("Expected a pair like 3,1, got {:?}", pair).into()),
+    }
 }
 
 #[cfg(test)]
@@ -86,4 +278,22 @@ mod tests {
 
         assert_eq!(part_02(&map), 336);
     }
+
+    #[test]
+    fn example_tree_hits() {
+        let map = read_file("example.txt").unwrap();
+
+        assert_eq!(
+            map.tree_hits(INIT_POSITION, SLOPE),
+            vec![
+                (6, 2),
+                (12, 4),
+                (15, 5),
+                (21, 7),
+                (24, 8),
+                (27, 9),
+                (30, 10)
+            ]
+        );
+    }
 }
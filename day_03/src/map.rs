@@ -0,0 +1,266 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::bits::{BitGrid, Row};
+
+pub const TREE: char = '#';
+pub const OPEN: char = '.';
+
+/// `(x, y)`, with `y` counting rows down from the top.
+pub type Position = (usize, usize);
+
+/// `(right, down)` per step, negative to go left or up.
+pub type Slope = (isize, isize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    Empty,
+    Ragged {
+        /// 1-based, like the line numbers of other parse errors.
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Empty => write!(f, "map has no cells"),
+            MapError::Ragged {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {} is {} wide, expected {} like the first line",
+                line, width, expected
+            ),
+        }
+    }
+}
+
+impl Error for MapError {}
+
+/// A rectangular map repeating in both directions, so every position is on it. Trees
+/// are one bit each, other terrain than open ground one bitset per char.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeMap {
+    trees: BitGrid,
+    terrain: Vec<(char, BitGrid)>,
+}
+
+impl TreeMap {
+    pub fn new(rows: Vec<Vec<char>>) -> Result<TreeMap, MapError> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+
+        if width == 0 {
+            return Err(MapError::Empty);
+        }
+
+        if let Some((index, cells)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(MapError::Ragged {
+                line: index + 1,
+                width: cells.len(),
+                expected: width,
+            });
+        }
+
+        let mut trees = BitGrid::new(width, rows.len());
+        let mut terrain: Vec<(char, BitGrid)> = Vec::new();
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                match cell {
+                    TREE => trees.set((x, y)),
+                    OPEN => (),
+                    _ => match terrain.iter_mut().find(|(c, _)| *c == cell) {
+                        Some((_, grid)) => grid.set((x, y)),
+                        None => {
+                            let mut grid = BitGrid::new(width, rows.len());
+
+                            grid.set((x, y));
+                            terrain.push((cell, grid));
+                        }
+                    },
+                }
+            }
+        }
+
+        Ok(TreeMap { trees, terrain })
+    }
+
+    pub fn width(&self) -> usize {
+        self.trees.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.trees.height()
+    }
+
+    /// The trees of row `y`, within bounds.
+    pub fn row(&self, y: usize) -> Row<'_> {
+        self.trees.row(y)
+    }
+
+    /// Whether the cell at `position`, wrapped onto the map, is a tree.
+    pub fn is_tree(&self, (x, y): Position) -> bool {
+        self.trees.contains((x % self.width(), y % self.height()))
+    }
+
+    /// The char at `position`, wrapped onto the map.
+    pub fn get(&self, position: Position) -> char {
+        let position = (position.0 % self.width(), position.1 % self.height());
+
+        if self.trees.contains(position) {
+            return TREE;
+        }
+
+        self.terrain
+            .iter()
+            .find(|(_, grid)| grid.contains(position))
+            .map_or(OPEN, |&(c, _)| c)
+    }
+
+    /// `steps` positions from `start` on, each `slope` from the previous, wrapped.
+    pub fn walk(&self, start: Position, slope: Slope, steps: usize) -> Walk {
+        Walk {
+            width: self.width(),
+            height: self.height(),
+            position: (start.0 % self.width(), start.1 % self.height()),
+            right: wrap(slope.0, self.width()),
+            down: wrap(slope.1, self.height()),
+            steps,
+        }
+    }
+
+    /// One pass from `start`: down or up until the bottom or top edge, or along the row
+    /// until the positions repeat for a horizontal `slope`.
+    pub fn path(&self, start: Position, slope: Slope) -> Walk {
+        let (x, y) = (start.0 % self.width(), start.1 % self.height());
+        let steps = match slope.1 {
+            0 => self.width() / gcd(self.width(), wrap(slope.0, self.width())),
+            down if down > 0 => (self.height() - 1 - y) / down as usize + 1,
+            up => y / up.unsigned_abs() + 1,
+        };
+
+        self.walk((x, y), slope, steps)
+    }
+
+    /// Every tree position along one pass, see `path`.
+    pub fn tree_hits(&self, start: Position, slope: Slope) -> Vec<Position> {
+        self.path(start, slope)
+            .filter(|&position| self.is_tree(position))
+            .collect()
+    }
+
+    pub fn count_trees(&self, start: Position, slope: Slope) -> usize {
+        self.path(start, slope)
+            .filter(|&position| self.is_tree(position))
+            .count()
+    }
+}
+
+/// One row per line.
+impl FromStr for TreeMap {
+    type Err = MapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TreeMap::new(s.lines().map(|line| line.chars().collect()).collect())
+    }
+}
+
+/// `offset` as the equivalent step in `0..size`.
+fn wrap(offset: isize, size: usize) -> usize {
+    offset.rem_euclid(size as isize) as usize
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+/// Positions along a slope, see `TreeMap::walk`.
+pub struct Walk {
+    width: usize,
+    height: usize,
+    position: Position,
+    right: usize,
+    down: usize,
+    steps: usize,
+}
+
+impl Iterator for Walk {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.steps == 0 {
+            return None;
+        }
+
+        let position = self.position;
+
+        self.steps -= 1;
+        self.position = (
+            (position.0 + self.right) % self.width,
+            (position.1 + self.down) % self.height,
+        );
+
+        Some(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> TreeMap {
+        "#..\n.#.\n..#\n#..".parse().unwrap()
+    }
+
+    #[test]
+    fn wrapping() {
+        let map = map();
+        let path = |start, slope| map.path(start, slope).collect::<Vec<_>>();
+
+        assert_eq!(path((0, 0), (4, 1)), vec![(0, 0), (1, 1), (2, 2), (0, 3)]);
+        assert_eq!(path((1, 3), (-1, -1)), vec![(1, 3), (0, 2), (2, 1), (1, 0)]);
+        assert_eq!(path((2, 1), (1, 0)), vec![(2, 1), (0, 1), (1, 1)]);
+        assert_eq!(path((2, 1), (0, 0)), vec![(2, 1)]);
+        assert_eq!(path((0, 1), (0, 2)), vec![(0, 1), (0, 3)]);
+        assert_eq!(
+            map.walk((0, 2), (1, 1), 3).collect::<Vec<_>>(),
+            vec![(0, 2), (1, 3), (2, 0)]
+        );
+        assert_eq!(map.count_trees((0, 0), (1, 1)), 4);
+        assert!(map.is_tree((3, 4)));
+        assert_eq!((map.get((4, 1)), map.get((1, 0))), (TREE, OPEN));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(TreeMap::new(vec![]), Err(MapError::Empty));
+        assert_eq!(
+            TreeMap::new(vec![vec!['.', '#'], vec!['.']]),
+            Err(MapError::Ragged {
+                line: 2,
+                width: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            TreeMap::new(vec![vec!['.', '#'], vec!['.']])
+                .unwrap_err()
+                .to_string(),
+            "line 2 is 1 wide, expected 2 like the first line"
+        );
+        assert_eq!("".parse::<TreeMap>(), Err(MapError::Empty));
+        assert_eq!(
+            ".#\n.".parse(),
+            TreeMap::new(vec![vec!['.', '#'], vec!['.']])
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::map::{Position, Slope, TreeMap};
+
+/// Slope colours for images, cycled when there are more slopes.
+const PALETTE: [[u8; 3]; 6] = [
+    [230, 25, 75],
+    [0, 130, 200],
+    [245, 130, 48],
+    [145, 30, 180],
+    [60, 180, 75],
+    [240, 50, 230],
+];
+const OPEN_COLOUR: [u8; 3] = [255, 255, 255];
+const TREE_COLOUR: [u8; 3] = [150, 150, 150];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cell {
+    Open,
+    Tree,
+    /// Visited by the slope with this index, the last one when several cross.
+    Visited {
+        slope: usize,
+        tree: bool,
+    },
+}
+
+/// The map repeated to the right (or left) as often as the paths need.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    width: usize,
+    cells: Vec<Vec<Cell>>,
+}
+
+/// Every slope's pass from `start` drawn on one canvas.
+pub fn render(map: &TreeMap, start: Position, slopes: &[Slope]) -> Canvas {
+    let width = map.width() as isize;
+    // unwrapped `x` of every visited position
+    let paths: Vec<Vec<(isize, usize)>> = slopes
+        .iter()
+        .map(|&slope| {
+            map.path(start, slope)
+                .enumerate()
+                .map(|(step, (_, y))| {
+                    (
+                        (start.0 % map.width()) as isize + step as isize * slope.0,
+                        y,
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    let xs = || paths.iter().flatten().map(|&(x, _)| x);
+    let first_tile = xs().min().unwrap_or(0).div_euclid(width);
+    let last_tile = xs().max().unwrap_or(0).div_euclid(width);
+    let offset = first_tile * width;
+    let canvas_width = ((last_tile - first_tile + 1) * width) as usize;
+
+    let mut cells: Vec<Vec<Cell>> = (0..map.height())
+        .map(|y| {
+            (0..canvas_width)
+                .map(|x| match map.is_tree((x % map.width(), y)) {
+                    true => Cell::Tree,
+                    false => Cell::Open,
+                })
+                .collect()
+        })
+        .collect();
+
+    for (slope, path) in paths.iter().enumerate() {
+        for &(x, y) in path.iter() {
+            let cell = &mut cells[y][(x - offset) as usize];
+            let tree = matches!(cell, Cell::Tree | Cell::Visited { tree: true, .. });
+
+            *cell = Cell::Visited { slope, tree };
+        }
+    }
+
+    Canvas {
+        width: canvas_width,
+        cells,
+    }
+}
+
+/// The puzzle's notation: `#` trees, `.` open, `X` and `O` where a path hits either.
+impl Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.iter() {
+            let line: String = row
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Open => '.',
+                    Cell::Tree => '#',
+                    Cell::Visited { tree: false, .. } => 'O',
+                    Cell::Visited { tree: true, .. } => 'X',
+                })
+                .collect();
+
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Canvas {
+    /// Binary PPM with `scale` by `scale` pixels per cell, visited cells in their slope's
+    /// colour, trees hit in a darker shade of it.
+    pub fn write_ppm<W: Write>(&self, mut writer: W, scale: usize) -> io::Result<()> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.width * scale,
+            self.cells.len() * scale
+        )?;
+
+        for row in self.cells.iter() {
+            let pixels: Vec<u8> = row
+                .iter()
+                .flat_map(|&cell| {
+                    let colour = match cell {
+                        Cell::Open => OPEN_COLOUR,
+                        Cell::Tree => TREE_COLOUR,
+                        Cell::Visited { slope, tree } => {
+                            let [red, green, blue] = PALETTE[slope % PALETTE.len()];
+
+                            match tree {
+                                true => [red / 2, green / 2, blue / 2],
+                                false => [red, green, blue],
+                            }
+                        }
+                    };
+
+                    colour.repeat(scale)
+                })
+                .collect();
+
+            for _ in 0..scale {
+                writer.write_all(&pixels)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> TreeMap {
+        "..#\n#..\n.#.\n...".parse().unwrap()
+    }
+
+    #[test]
+    fn ascii() {
+        let map = map();
+
+        assert_eq!(
+            render(&map, (0, 0), &[(2, 1)]).to_string(),
+            "O.#..#..#\n#.O#..#..\n.#..X..#.\n......O..\n"
+        );
+        // the path going left needs a repeat on that side
+        assert_eq!(
+            render(&map, (0, 0), &[(-1, 1), (1, 1)]).to_string(),
+            "..#O.#..#\n#.O#O.#..\n.X..#O.#.\nO.....O..\n"
+        );
+    }
+
+    #[test]
+    fn ppm() {
+        let mut ppm = Vec::new();
+
+        render(&map(), (0, 0), &[(1, 1)])
+            .write_ppm(&mut ppm, 2)
+            .unwrap();
+
+        assert!(ppm.starts_with(b"P6\n12 8\n255\n"));
+        assert_eq!(ppm.len(), 12 + 12 * 8 * 3);
+        assert_eq!(ppm[12..15], PALETTE[0]);
+        assert_eq!(ppm[15..18], PALETTE[0]);
+        assert_eq!(ppm[18..21], OPEN_COLOUR);
+    }
+}
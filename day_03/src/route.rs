@@ -0,0 +1,94 @@
+use crate::map::{Position, TreeMap};
+
+/// A path down the map and the trees on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub trees: usize,
+    /// One position per row, from the start down to the bottom row.
+    pub path: Vec<Position>,
+}
+
+impl Route {
+    /// How far right every step moves, wrapped to the map width.
+    pub fn moves(&self, width: usize) -> Vec<usize> {
+        self.path
+            .windows(2)
+            .map(|pair| (pair[1].0 + width - pair[0].0) % width)
+            .collect()
+    }
+}
+
+/// The route from `start` to the bottom row hitting the fewest trees, when every step
+/// moves down one and right `0..=max_right`. Moving right by the width or more ends up
+/// in a column a shorter move reaches as well, so those moves are never tried. On a tie
+/// the shorter move wins.
+pub fn least_trees(map: &TreeMap, start: Position, max_right: usize) -> Route {
+    let (width, height) = (map.width(), map.height());
+    let (x, y) = (start.0 % width, start.1 % height);
+    let max_right = max_right.min(width - 1);
+    let tree = |position| usize::from(map.is_tree(position));
+    // per row below the start, the column every cell is best reached from
+    let mut came_from: Vec<Vec<usize>> = Vec::with_capacity(height - y - 1);
+    let mut trees: Vec<Option<usize>> = vec![None; width];
+
+    trees[x] = Some(tree((x, y)));
+
+    for y in y + 1..height {
+        let mut row_trees = vec![None; width];
+        let mut row_from = vec![0; width];
+
+        for x in 0..width {
+            let best = (0..=max_right)
+                .map(|right| (x + width - right) % width)
+                .filter_map(|from| trees[from].map(|trees| (trees, from)))
+                .min_by_key(|&(trees, _)| trees);
+
+            if let Some((best_trees, from)) = best {
+                row_trees[x] = Some(best_trees + tree((x, y)));
+                row_from[x] = from;
+            }
+        }
+
+        trees = row_trees;
+        came_from.push(row_from);
+    }
+
+    let (least, mut x) = trees
+        .iter()
+        .enumerate()
+        .filter_map(|(x, trees)| trees.map(|trees| (trees, x)))
+        .min()
+        .expect("the start column is always reachable");
+    let mut path = vec![(x, height - 1)];
+
+    for (row, row_from) in came_from.iter().enumerate().rev() {
+        x = row_from[x];
+        path.push((x, y + row));
+    }
+
+    path.reverse();
+
+    Route { trees: least, path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fewest() {
+        let map: TreeMap = "....\n#.##\n##.#\n##.#\n.###".parse().unwrap();
+        let route = least_trees(&map, (0, 0), 1);
+
+        assert_eq!(route.trees, 1);
+        assert_eq!(route.path, vec![(0, 0), (1, 1), (2, 2), (3, 3), (0, 4)]);
+        assert_eq!(route.moves(map.width()), vec![1, 1, 1, 1]);
+        assert_eq!(least_trees(&map, (0, 0), 0).trees, 3);
+        // wrapping around to the left edge and moving more than the width
+        assert_eq!(
+            least_trees(&map, (3, 1), 9).path,
+            vec![(3, 1), (2, 2), (2, 3), (0, 4)]
+        );
+        assert_eq!(least_trees(&map, (0, 4), 2).path, vec![(0, 4)]);
+    }
+}
@@ -0,0 +1,113 @@
+use std::ops::RangeInclusive;
+
+use crate::map::{Position, Slope, TreeMap};
+
+/// The slopes hitting the fewest and the most trees, all of them on a tie.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extremes {
+    pub fewest: (usize, Vec<Slope>),
+    pub most: (usize, Vec<Slope>),
+}
+
+impl Extremes {
+    fn add(&mut self, slope: Slope, trees: usize) {
+        let (fewer, more) = (trees < self.fewest.0, trees > self.most.0);
+
+        for (extreme, better) in [(&mut self.fewest, fewer), (&mut self.most, more)] {
+            if better {
+                *extreme = (trees, vec![slope]);
+            } else if trees == extreme.0 {
+                extreme.1.push(slope);
+            }
+        }
+    }
+}
+
+/// Tries every slope within the bounds from `start`, `None` when either range is empty.
+pub fn search(
+    map: &TreeMap,
+    start: Position,
+    rights: RangeInclusive<isize>,
+    downs: RangeInclusive<isize>,
+) -> Option<Extremes> {
+    let mut extremes: Option<Extremes> = None;
+
+    for down in downs {
+        for (slope, trees) in tree_counts(map, start, rights.clone(), down) {
+            match extremes.as_mut() {
+                Some(extremes) => extremes.add(slope, trees),
+                None => {
+                    extremes = Some(Extremes {
+                        fewest: (trees, vec![slope]),
+                        most: (trees, vec![slope]),
+                    })
+                }
+            }
+        }
+    }
+
+    extremes
+}
+
+/// Trees along every `(right, down)` slope, in one pass over the rows a `down` slope
+/// visits. Those are the same whatever `right` is, except for horizontal slopes, whose
+/// paths are as long as it takes to come back to `start` and are walked one by one.
+fn tree_counts(
+    map: &TreeMap,
+    start: Position,
+    rights: RangeInclusive<isize>,
+    down: isize,
+) -> Vec<(Slope, usize)> {
+    if down == 0 {
+        return rights
+            .map(|right| ((right, down), map.count_trees(start, (right, down))))
+            .collect();
+    }
+
+    let width = map.width() as isize;
+    let steps: Vec<usize> = rights
+        .clone()
+        .map(|right| right.rem_euclid(width) as usize)
+        .collect();
+    let mut columns = vec![start.0 % map.width(); steps.len()];
+    let mut counts = vec![0; steps.len()];
+
+    for (_, y) in map.path(start, (0, down)) {
+        let row = map.row(y);
+
+        for ((x, count), step) in columns.iter_mut().zip(counts.iter_mut()).zip(steps.iter()) {
+            if row.contains(*x) {
+                *count += 1;
+            }
+
+            *x = (*x + step) % map.width();
+        }
+    }
+
+    rights.map(|right| (right, down)).zip(counts).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extremes() {
+        let map: TreeMap = "#..\n.#.\n..#\n#..".parse().unwrap();
+
+        for down in -2..=2 {
+            for (slope, trees) in tree_counts(&map, (1, 2), -4..=4, down) {
+                assert_eq!(trees, map.count_trees((1, 2), slope), "{:?}", slope);
+            }
+        }
+
+        assert_eq!(
+            search(&map, (0, 0), 0..=2, 1..=1),
+            Some(Extremes {
+                fewest: (2, vec![(0, 1), (2, 1)]),
+                most: (4, vec![(1, 1)]),
+            })
+        );
+        assert_eq!(search(&map, (0, 0), RangeInclusive::new(1, 0), 1..=2), None);
+    }
+}
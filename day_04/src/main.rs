@@ -1,202 +1,230 @@
 use std::collections::HashMap;
-use std::fmt::Display;
+use std::env;
+use std::error::Error;
 use std::fs;
-use std::ops::RangeInclusive;
 use std::time::Instant;
-use std::{error::Error, str::FromStr};
 
-const BYR_VALID_RANGE: RangeInclusive<usize> = 1920..=2002;
-const IYR_VALID_RANGE: RangeInclusive<usize> = 2010..=2020;
-const EYR_VALID_RANGE: RangeInclusive<usize> = 2020..=2030;
-const VALID_CM_HEIGHTS: RangeInclusive<usize> = 150..=193;
-const VALID_IN_HEIGHTS: RangeInclusive<usize> = 59..=76;
-const VALID_EYE_COLORS: &'static [&'static str] =
-    &["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+use aoc::{debug, info, log};
+
+use schema::{Document, DocumentError, Schema};
+
+mod pattern;
+mod schema;
+
+const REPORTED_REASONS: usize = 10;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let passports = read_file("input.txt")?;
+    log::init();
+
+    let args: Vec<String> = env::args().collect();
+    let print_report = args.iter().any(|arg| arg == "--report");
+    // `--schema=<file>` validates another document type, passports by default
+    let schema_path = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix("--schema="))
+        .unwrap_or("passport.schema");
+
+    let schema = read_schema(schema_path)?;
+    let records = read_records("input.txt", &schema)?;
 
     let start = Instant::now();
 
-    println!("Answer 1: {:?}", part_01(&passports));
+    println!("Answer 1: {:?}", part_01(&records));
     println!("Completed in {:?}", start.elapsed());
 
     let start = Instant::now();
 
-    println!("Answer 2: {:?}", part_02(&passports));
+    println!("Answer 2: {:?}", part_02(&schema, &records));
     println!("Completed in {:?}", start.elapsed());
 
-    Ok(())
-}
-
-fn part_01(passports: &Vec<Passport>) -> usize {
-    passports.iter().count()
-}
+    if print_report {
+        report(&schema, &records);
+    }
 
-fn part_02(passports: &Vec<Passport>) -> usize {
-    passports.iter().filter(|pass| pass.is_valid()).count()
+    Ok(())
 }
 
-#[derive(Debug)]
-struct Passport {
-    birth_year: String,
-    issue_year: String,
-    expr_year: String,
-    height: String,
-    hair_color: String,
-    eye_color: String,
-    pass_id: String,
-    country_id: Option<String>,
+fn part_01(records: &[Result<Document, DocumentError>]) -> usize {
+    records.iter().filter(|record| record.is_ok()).count()
 }
 
-enum PassportError {
-    MissingField,
+fn part_02(schema: &Schema, records: &[Result<Document, DocumentError>]) -> usize {
+    records
+        .iter()
+        .filter_map(|record| record.as_ref().ok())
+        .filter(|document| schema.is_valid(document))
+        .count()
 }
 
-impl Display for PassportError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            PassportError::MissingField => f.write_str("Missing field"),
+/// Every document with all of its failures, then the most common failures in the batch.
+fn report(schema: &Schema, records: &[Result<Document, DocumentError>]) {
+    for (index, record) in records.iter().enumerate() {
+        let failures: Vec<String> = match record {
+            Ok(document) => schema
+                .validate(document)
+                .iter()
+                .map(|error| error.to_string())
+                .collect(),
+            Err(error) => vec![error.to_string()],
+        };
+
+        if !failures.is_empty() {
+            println!("  document {}: {}", index + 1, failures.join("; "));
         }
     }
-}
 
-impl Passport {
-    fn is_valid(&self) -> bool {
-        [
-            is_number_valid(&self.birth_year, BYR_VALID_RANGE),
-            is_number_valid(&self.issue_year, IYR_VALID_RANGE),
-            is_number_valid(&self.expr_year, EYR_VALID_RANGE),
-            is_height_valid(&self.height),
-            is_hair_color_valid(&self.hair_color),
-            is_eye_color_valid(&self.eye_color),
-            is_pid_valid(&self.pass_id),
-        ]
+    println!("  most common failures:");
+
+    for (reason, count) in failure_reasons(schema, records)
         .iter()
-        .all(|&x| x)
+        .take(REPORTED_REASONS)
+    {
+        println!("  {:>5} {}", count, reason);
     }
 }
 
-fn is_number_valid(number: &String, range: RangeInclusive<usize>) -> bool {
-    number
-        .parse::<usize>()
-        .ok()
-        .map(|n| range.contains(&n))
-        .unwrap_or(false)
-}
-
-fn is_height_valid(height: &String) -> bool {
-    match height.strip_suffix("cm") {
-        Some(rest) => {
-            return rest
-                .parse::<usize>()
-                .ok()
-                .map(|h| VALID_CM_HEIGHTS.contains(&h))
-                .unwrap_or(false)
+/// Failures without the offending values, most common first.
+fn failure_reasons(
+    schema: &Schema,
+    records: &[Result<Document, DocumentError>],
+) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for record in records.iter() {
+        let reasons = match record {
+            Ok(document) => schema
+                .validate(document)
+                .iter()
+                .map(|error| format!("{} {}", error.field, error.rule))
+                .collect(),
+            Err(error) => vec![error.to_string()],
+        };
+
+        for reason in reasons {
+            *counts.entry(reason).or_insert(0) += 1;
         }
-        None => match height.strip_suffix("in") {
-            Some(rest) => {
-                return rest
-                    .parse::<usize>()
-                    .ok()
-                    .map(|h| VALID_IN_HEIGHTS.contains(&h))
-                    .unwrap_or(false);
-            }
-            None => false,
-        },
     }
-}
 
-fn is_hair_color_valid(hair_color: &String) -> bool {
-    match hair_color.strip_prefix('#') {
-        Some(rest) => u32::from_str_radix(rest, 16).is_ok(),
-        None => false,
-    }
-}
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
 
-fn is_eye_color_valid(eye_color: &String) -> bool {
-    VALID_EYE_COLORS.contains(&eye_color.as_str())
-}
+    counts.sort_by(|(reason_01, count_01), (reason_02, count_02)| {
+        count_02.cmp(count_01).then(reason_01.cmp(reason_02))
+    });
 
-fn is_pid_valid(passport_id: &String) -> bool {
-    passport_id.matches(char::is_numeric).count() == 9
+    counts
 }
 
-impl FromStr for Passport {
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut mapping: HashMap<String, String> = HashMap::new();
-        let fields = s.split_whitespace();
+fn read_schema(filename: &str) -> Result<Schema, Box<dyn Error>> {
+    let schema: Schema = fs::read_to_string(filename)?.parse()?;
 
-        for field in fields {
-            let key_value: Vec<&str> = field.split(':').collect();
+    info!("parsed {} fields from {}", schema.0.len(), filename);
 
-            mapping.insert(key_value[0].to_string(), key_value[1].to_string());
-        }
+    Ok(schema)
+}
 
-        Ok(Passport {
-            birth_year: mapping
-                .get("byr")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            issue_year: mapping
-                .get("iyr")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            expr_year: mapping
-                .get("eyr")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            height: mapping
-                .get("hgt")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            hair_color: mapping
-                .get("hcl")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            eye_color: mapping
-                .get("ecl")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            pass_id: mapping
-                .get("pid")
-                .ok_or(PassportError::MissingField)?
-                .to_string(),
-            country_id: match mapping.get("cid") {
-                Some(s) => Some(s.to_string()),
-                None => None,
-            },
-        })
+fn read_records(
+    filename: &str,
+    schema: &Schema,
+) -> std::io::Result<Vec<Result<Document, DocumentError>>> {
+    // CRLF files would otherwise have no `\n\n` between records
+    let input = fs::read_to_string(filename)?.replace("\r\n", "\n");
+    let records: Vec<Result<Document, DocumentError>> = input
+        .split("\n\n")
+        .map(|record| schema.document(record))
+        .collect();
+
+    for (index, record) in records.iter().enumerate() {
+        if let Err(error) = record {
+            debug!("skipping document {}: {}", index + 1, error);
+        }
     }
 
-    type Err = PassportError;
-}
-
-fn read_file(filename: &str) -> std::io::Result<Vec<Passport>> {
-    let input = fs::read_to_string(filename)?;
+    info!(
+        "parsed {} documents from {}",
+        records.iter().filter(|record| record.is_ok()).count(),
+        filename
+    );
 
-    Ok(input
-        .split("\n\n") // \r\n\r\n on Windows
-        .map(|id| id.to_string().replace("\n", " ")) // \r\n on Windows
-        .filter_map(|id| id.parse().ok())
-        .collect())
+    Ok(records)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn passport() -> Schema {
+        read_schema("passport.schema").unwrap()
+    }
+
     #[test]
     fn example_01() {
-        let passports = read_file("example.txt").unwrap();
+        let records = read_records("example.txt", &passport()).unwrap();
 
-        assert_eq!(part_01(&passports), 2);
+        assert_eq!(part_01(&records), 2);
     }
 
     #[test]
     fn example_02() {
-        let passports = read_file("example_02.txt").unwrap();
+        let schema = passport();
+        let records = read_records("example_02.txt", &schema).unwrap();
+
+        assert_eq!(part_02(&schema, &records), 4);
+    }
+
+    #[test]
+    fn crlf() {
+        let path = std::env::temp_dir().join(format!("day_04_crlf_{}", std::process::id()));
+        let crlf = fs::read_to_string("example.txt")
+            .unwrap()
+            .replace('\n', "\r\n");
+
+        fs::write(&path, crlf).unwrap();
+
+        let records = read_records(path.to_str().unwrap(), &passport()).unwrap();
+
+        fs::remove_file(path).unwrap();
+        assert_eq!(part_01(&records), 2);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let schema = passport();
+        let records = read_records("example_02.txt", &schema).unwrap();
+        let failures = |index: usize| -> Vec<String> {
+            match &records[index] {
+                Ok(document) => schema
+                    .validate(document)
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect(),
+                Err(error) => vec![error.to_string()],
+            }
+        };
+
+        assert_eq!(
+            failures(0),
+            vec![
+                "eyr: 1972 outside 2020..=2030",
+                "hgt: 170 has no cm or in unit",
+                "pid: 186cm does not match \\d{9}"
+            ]
+        );
+        assert_eq!(
+            schema.document("hgt:200cm").unwrap_err(),
+            DocumentError::MissingField("byr".to_string())
+        );
+        assert_eq!(
+            failure_reasons(&schema, &records)[0],
+            ("eyr outside 2020..=2030".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn credentials() {
+        let credentials = read_schema("north_pole_credentials.schema").unwrap();
+        let records = read_records("example_02.txt", &credentials).unwrap();
 
-        assert_eq!(part_02(&passports), 4);
+        assert_eq!(part_02(&credentials, &records), 4);
+        assert!(credentials.0.iter().all(|field| field.name != "cid"));
     }
 }
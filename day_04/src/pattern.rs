@@ -0,0 +1,206 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    Char(char),
+    Any,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Char(expected) => c == *expected,
+            Atom::Any => true,
+            Atom::Class { negated, ranges } => {
+                ranges.iter().any(|&(low, high)| (low..=high).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+/// An atom repeated `min..=max` times.
+#[derive(Debug, Clone, PartialEq)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: usize,
+}
+
+/// A small regular expression matching whole values: literal chars, `.`, classes like
+/// `[0-9a-f]` or `[^x]`, `\d`, escaped chars, and the quantifiers `?`, `*`, `+`, `{n}`,
+/// `{n,}` and `{n,m}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+impl Pattern {
+    pub fn is_match(&self, value: &str) -> bool {
+        let chars: Vec<char> = value.chars().collect();
+
+        matches(&self.pieces, &chars)
+    }
+}
+
+/// Backtracks from the longest repeat of every piece down to its minimum.
+fn matches(pieces: &[Piece], chars: &[char]) -> bool {
+    match pieces.split_first() {
+        None => chars.is_empty(),
+        Some((piece, rest)) => {
+            let longest = chars
+                .iter()
+                .take(piece.max)
+                .take_while(|&&c| piece.atom.matches(c))
+                .count();
+
+            longest >= piece.min
+                && (piece.min..=longest)
+                    .rev()
+                    .any(|repeat| matches(rest, &chars[repeat..]))
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars().peekable();
+        let mut pieces = Vec::new();
+
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '.' => Atom::Any,
+                '\\' => match chars.next() {
+                    Some('d') => Atom::Class {
+                        negated: false,
+                        ranges: vec![('0', '9')],
+                    },
+                    Some(escaped) => Atom::Char(escaped),
+                    None => return Err("pattern ends in \\".to_string()),
+                },
+                '[' => {
+                    let negated = chars.next_if_eq(&'^').is_some();
+                    let mut ranges = Vec::new();
+
+                    loop {
+                        let low = match chars.next() {
+                            Some(']') if !ranges.is_empty() => break,
+                            Some('\\') => chars.next(),
+                            low => low,
+                        }
+                        .ok_or("unclosed [")?;
+                        let high = match chars.next_if_eq(&'-') {
+                            Some(_) => {
+                                chars.next().filter(|&c| c != ']').ok_or("unclosed range")?
+                            }
+                            None => low,
+                        };
+
+                        ranges.push((low, high));
+                    }
+
+                    Atom::Class { negated, ranges }
+                }
+                '?' | '*' | '+' | '{' => return Err(format!("nothing to repeat before {}", c)),
+                c => Atom::Char(c),
+            };
+            let (min, max) = match chars.next_if(|c| matches!(c, '?' | '*' | '+' | '{')) {
+                Some('?') => (0, 1),
+                Some('*') => (0, usize::MAX),
+                Some('+') => (1, usize::MAX),
+                Some(_) => {
+                    let mut repeat = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => repeat.push(c),
+                            None => return Err("unclosed {".to_string()),
+                        }
+                    }
+
+                    let number = |n: &str| {
+                        n.parse::<usize>()
+                            .map_err(|_| format!("invalid repeat {{{}}}", repeat))
+                    };
+
+                    let (min, max) = match repeat.split_once(',') {
+                        None => (number(&repeat)?, number(&repeat)?),
+                        Some((min, "")) => (number(min)?, usize::MAX),
+                        Some((min, max)) => (number(min)?, number(max)?),
+                    };
+
+                    if min > max {
+                        return Err(format!(
+                            "invalid repeat {{{}}}, {} is above {}",
+                            repeat, min, max
+                        ));
+                    }
+
+                    (min, max)
+                }
+                None => (1, 1),
+            };
+
+            pieces.push(Piece { atom, min, max });
+        }
+
+        Ok(Pattern {
+            source: s.to_string(),
+            pieces,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, value: &str) -> bool {
+        pattern.parse::<Pattern>().unwrap().is_match(value)
+    }
+
+    #[test]
+    fn matching() {
+        assert!(is_match("#[0-9a-f]{6}", "#123abc"));
+        assert!(!is_match("#[0-9a-f]{6}", "#123abz"));
+        assert!(!is_match("#[0-9a-f]{6}", "123abc"));
+        assert!(is_match("\\d{9}", "000000001"));
+        assert!(!is_match("\\d{9}", "0123456789"));
+        assert!(is_match("a.*b+c?", "axxbb"));
+        assert!(is_match("[^x]{2,}\\.", "ab."));
+        assert!(!is_match("[^x]{2,}\\.", "ax."));
+        assert!(is_match("a{1,2}ab", "aab"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(
+            "*a".parse::<Pattern>(),
+            Err("nothing to repeat before *".to_string())
+        );
+        assert_eq!("[a-".parse::<Pattern>(), Err("unclosed range".to_string()));
+        assert_eq!(
+            "a{x}".parse::<Pattern>(),
+            Err("invalid repeat {x}".to_string())
+        );
+        assert_eq!("a{3".parse::<Pattern>(), Err("unclosed {".to_string()));
+        assert_eq!(
+            "a{5,2}".parse::<Pattern>(),
+            Err("invalid repeat {5,2}, 5 is above 2".to_string())
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::pattern::Pattern;
+
+const KINDS: [&str; 4] = ["int", "unit", "pattern", "enum"];
+
+/// What a field's value has to look like.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+    Any,
+    Int(RangeInclusive<i64>),
+    /// A number with one of the units, each with its own range.
+    Unit(Vec<(String, RangeInclusive<i64>)>),
+    Pattern(Pattern),
+    Enum(Vec<String>),
+}
+
+impl Kind {
+    /// The rule `value` breaks, if any.
+    fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Kind::Any => Ok(()),
+            Kind::Int(range) => check_int(value, range),
+            Kind::Unit(units) => {
+                let unit = units.iter().find_map(|(unit, range)| {
+                    value.strip_suffix(unit.as_str()).map(|n| (n, range))
+                });
+
+                match unit {
+                    Some((number, range)) => check_int(number, range),
+                    None => {
+                        let names: Vec<&str> =
+                            units.iter().map(|(unit, _)| unit.as_str()).collect();
+
+                        Err(format!("has no {} unit", names.join(" or ")))
+                    }
+                }
+            }
+            Kind::Pattern(pattern) => match pattern.is_match(value) {
+                true => Ok(()),
+                false => Err(format!("does not match {}", pattern)),
+            },
+            Kind::Enum(values) => match values.iter().any(|allowed| allowed == value) {
+                true => Ok(()),
+                false => Err(format!("is not one of {}", values.join(", "))),
+            },
+        }
+    }
+}
+
+fn check_int(number: &str, range: &RangeInclusive<i64>) -> Result<(), String> {
+    match number.parse::<i64>() {
+        Ok(n) if range.contains(&n) => Ok(()),
+        Ok(_) => Err(format!("outside {:?}", range)),
+        Err(_) => Err("is not a number".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub required: bool,
+    pub kind: Kind,
+}
+
+/// The fields of a document type, fields it doesn't declare are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema(pub Vec<Field>);
+
+/// The `key:value` fields of one record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document(pub HashMap<String, String>);
+
+#[derive(Debug, PartialEq)]
+pub enum DocumentError {
+    /// The first required field that is missing.
+    MissingField(String),
+}
+
+impl Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentError::MissingField(field) => write!(f, "Missing field {}", field),
+        }
+    }
+}
+
+/// A field breaking its rule, e.g. `hgt: 200cm outside 150..=193`.
+#[derive(Debug, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub value: String,
+    pub rule: String,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {}", self.field, self.value, self.rule)
+    }
+}
+
+impl Schema {
+    /// The whitespace separated `key:value` fields of `record`, if it has every
+    /// required one.
+    pub fn document(&self, record: &str) -> Result<Document, DocumentError> {
+        let fields: HashMap<String, String> = record
+            .split_whitespace()
+            .filter_map(|field| field.split_once(':'))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        match self
+            .0
+            .iter()
+            .find(|field| field.required && !fields.contains_key(&field.name))
+        {
+            Some(field) => Err(DocumentError::MissingField(field.name.clone())),
+            None => Ok(Document(fields)),
+        }
+    }
+
+    /// Every present field breaking its rule, in schema order.
+    pub fn validate(&self, document: &Document) -> Vec<FieldError> {
+        self.0
+            .iter()
+            .filter_map(|field| {
+                let value = document.0.get(&field.name)?;
+
+                field.kind.check(value).err().map(|rule| FieldError {
+                    field: field.name.clone(),
+                    value: value.clone(),
+                    rule,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_valid(&self, document: &Document) -> bool {
+        self.validate(document).is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseSchemaError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ParseSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseSchemaError {}
+
+/// One `<field> required|optional [<kind> <arguments>]` line per field, blank lines and
+/// lines starting with `//` are skipped. The kinds are
+///
+/// - `int 1920..=2002`
+/// - `unit cm 150..=193 in 59..=76`
+/// - `pattern #[0-9a-f]{6}`, see `Pattern`
+/// - `enum amb blu brn`
+///
+/// and a field without one only has to be present.
+impl FromStr for Schema {
+    type Err = ParseSchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields: Vec<Field> = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            let field = parse_field(line).map_err(|message| ParseSchemaError {
+                line: index + 1,
+                message,
+            })?;
+
+            if fields.iter().any(|other| other.name == field.name) {
+                return Err(ParseSchemaError {
+                    line: index + 1,
+                    message: format!("field {} is already declared", field.name),
+                });
+            }
+
+            fields.push(field);
+        }
+
+        Ok(Schema(fields))
+    }
+}
+
+fn parse_field(line: &str) -> Result<Field, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (name, presence, kind, arguments) = match words[..] {
+        [name, presence] => (name, presence, None, &[][..]),
+        [name, presence, kind, ref arguments @ ..] => (name, presence, Some(kind), arguments),
+        _ => {
+            return Err(format!(
+                "expected `<field> required|optional`, got {:?}",
+                line
+            ))
+        }
+    };
+    let required = match presence {
+        "required" => true,
+        "optional" => false,
+        _ => return Err(format!("expected required or optional, got {:?}", presence)),
+    };
+    let kind = match (kind, arguments) {
+        (None, _) => Kind::Any,
+        (Some("int"), [range]) => Kind::Int(parse_range(range)?),
+        (Some("unit"), units) if !units.is_empty() && units.len() % 2 == 0 => Kind::Unit(
+            units
+                .chunks(2)
+                .map(|unit| Ok((unit[0].to_string(), parse_range(unit[1])?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        (Some("pattern"), [pattern]) => Kind::Pattern(pattern.parse()?),
+        (Some("enum"), values) if !values.is_empty() => {
+            Kind::Enum(values.iter().map(|value| value.to_string()).collect())
+        }
+        (Some(kind), _) if !KINDS.contains(&kind) => {
+            return Err(format!(
+                "unknown kind {:?}, expected one of {}",
+                kind,
+                KINDS.join(", ")
+            ))
+        }
+        (Some(kind), _) => {
+            return Err(format!(
+                "invalid {} arguments {:?}",
+                kind,
+                arguments.join(" ")
+            ))
+        }
+    };
+
+    Ok(Field {
+        name: name.to_string(),
+        required,
+        kind,
+    })
+}
+
+fn parse_range(range: &str) -> Result<RangeInclusive<i64>, String> {
+    let error = || format!("expected a range like 150..=193, got {:?}", range);
+    let (low, high) = range.split_once("..=").ok_or_else(error)?;
+
+    Ok(low.parse().map_err(|_| error())?..=high.parse().map_err(|_| error())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        let schema: Schema = "// heights\nhgt required unit cm 150..=193 in 59..=76\n\n\
+                              ecl optional enum amb blu\nhcl optional pattern #[0-9a-f]{6}\n\
+                              cid optional"
+            .parse()
+            .unwrap();
+        let failures = |record: &str| -> Vec<String> {
+            let document = schema.document(record).unwrap();
+
+            schema
+                .validate(&document)
+                .iter()
+                .map(|error| error.to_string())
+                .collect()
+        };
+
+        assert_eq!(failures("hgt:60in cid:x ecl:blu"), Vec::<String>::new());
+        assert_eq!(
+            failures("hgt:200cm ecl:red hcl:#12345"),
+            vec![
+                "hgt: 200cm outside 150..=193",
+                "ecl: red is not one of amb, blu",
+                "hcl: #12345 does not match #[0-9a-f]{6}"
+            ]
+        );
+        assert_eq!(failures("hgt:60"), vec!["hgt: 60 has no cm or in unit"]);
+        assert_eq!(
+            schema.document("ecl:amb"),
+            Err(DocumentError::MissingField("hgt".to_string()))
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let error = |schema: &str| schema.parse::<Schema>().unwrap_err().to_string();
+
+        assert_eq!(
+            error("byr"),
+            "line 1: expected `<field> required|optional`, got \"byr\""
+        );
+        assert_eq!(
+            error("byr needed"),
+            "line 1: expected required or optional, got \"needed\""
+        );
+        assert_eq!(
+            error("byr required int 1920"),
+            "line 1: expected a range like 150..=193, got \"1920\""
+        );
+        assert_eq!(
+            error("hgt required unit cm"),
+            "line 1: invalid unit arguments \"cm\""
+        );
+        assert_eq!(
+            error("byr required float"),
+            "line 1: unknown kind \"float\", expected one of int, unit, pattern, enum"
+        );
+        assert_eq!(
+            error("\nbyr optional\nbyr required"),
+            "line 3: field byr is already declared"
+        );
+    }
+}
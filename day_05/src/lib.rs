@@ -0,0 +1,84 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+// Logging needs the `std` feature, without it this expands to nothing.
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "std")]
+        aoc::warn!($($arg)+);
+    };
+}
+
+const SEATS: usize = 1 << 10;
+
+pub fn part_01<I: IntoIterator<Item = u16>>(seats: I) -> u16 {
+    seats.into_iter().max().unwrap()
+}
+
+/// Finds the free seat with both neighbours taken, keeping one flag per seat so the
+/// seats can be streamed in. Seats outside the plane are logged and skipped.
+pub fn part_02<I: IntoIterator<Item = u16>>(seats: I) -> Option<u16> {
+    let mut taken = [false; SEATS];
+
+    for seat in seats {
+        match taken.get_mut(seat as usize) {
+            Some(taken) => *taken = true,
+            None => {
+                warn!("skipping seat {}: outside 0..{}", seat, SEATS);
+            }
+        }
+    }
+
+    (1..SEATS - 1)
+        .find(|&seat| !taken[seat] && taken[seat - 1] && taken[seat + 1])
+        .map(|seat| seat as u16)
+}
+
+/// Splits a seat id into its `(row, column)`.
+pub fn seat_position(seat: u16) -> (u16, u16) {
+    (seat >> 3, seat & 0b111)
+}
+
+/// Encodes a seat id back into its boarding pass.
+pub fn boarding_pass(seat: u16) -> String {
+    (0..10)
+        .rev()
+        .enumerate()
+        .map(|(index, bit)| match (index < 7, seat >> bit & 1 == 1) {
+            (true, false) => 'F',
+            (true, true) => 'B',
+            (false, false) => 'L',
+            (false, true) => 'R',
+        })
+        .collect()
+}
+
+pub fn calculate_seat(boarding_pass: &str) -> u16 {
+    boarding_pass.chars().fold(0, |acc, character| {
+        (acc << 1) | matches!(character, 'B' | 'R') as u16
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_01() {
+        assert_eq!(calculate_seat("BFFFBBFRRR"), 567);
+        assert_eq!(calculate_seat("FFFBBBFRRR"), 119);
+        assert_eq!(calculate_seat("BBFFBBFRLL"), 820);
+        assert_eq!(seat_position(567), (70, 7));
+        assert_eq!(seat_position(820), (102, 4));
+        assert_eq!(boarding_pass(567), "BFFFBBFRRR");
+    }
+
+    #[test]
+    fn example_02() {
+        assert_eq!(part_02([118, 119, 121, 122, 123]), Some(120));
+        assert_eq!(part_02([118, 119, 2000, 120]), None);
+    }
+}
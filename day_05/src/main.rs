@@ -1,58 +1,71 @@
+use std::env;
 use std::error::Error;
-use std::fs;
+use std::io;
 use std::time::Instant;
 
+use aoc::input::{stream, Input};
+use aoc::{info, log};
+use day_05::{boarding_pass, calculate_seat, part_01, part_02, seat_position};
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let boarding_passes = read_file("input.txt")?;
+    log::init();
+
+    let explain = env::args().any(|arg| arg == "--explain");
 
     let start = Instant::now();
 
-    println!("Answer 1: {:?}", part_01(&boarding_passes));
+    println!(
+        "Answer 1: {:?}",
+        stream(read_seats("input.txt")?, |seats| part_01(seats))?
+    );
     println!("Completed in {:?}", start.elapsed());
 
-    println!("Answer 2: {:?}", part_02(&boarding_passes));
+    let seat = stream(read_seats("input.txt")?, |seats| part_02(seats))?
+        .ok_or("no free seat between two taken ones")?;
+
+    println!("Answer 2: {:?}", seat);
     println!("Completed in {:?}", start.elapsed());
 
+    if explain {
+        explain_seat("input.txt", seat)?;
+    }
+
     Ok(())
 }
 
-fn part_01(passes: &Vec<String>) -> u16 {
-    passes.iter().map(|x| calculate_seat(x)).max().unwrap()
-}
+fn explain_seat(filename: &str, seat: u16) -> io::Result<()> {
+    let (row, column) = seat_position(seat);
 
-fn part_02(passes: &Vec<String>) -> u16 {
-    let mut seats: Vec<u16> = passes.iter().map(|x| calculate_seat(x)).collect();
+    println!(
+        "  seat {} (row {}, column {}) is missing",
+        seat, row, column
+    );
 
-    seats.sort_unstable();
+    for neighbour in seat.checked_sub(1).into_iter().chain(seat.checked_add(1)) {
+        let (row, column) = seat_position(neighbour);
 
-    let result = (seats[0]..=seats[seats.len() - 1])
-        .zip(seats.iter())
-        .find(|(expected, seat)| expected != *seat)
-        .unwrap();
+        let taken = stream(read_seats(filename)?, |mut seats| {
+            seats.any(|other| other == neighbour)
+        })?;
 
-    result.0
-}
-
-fn calculate_seat(boarding_pass: &String) -> u16 {
-    boarding_pass.chars().fold(0, |acc, character| {
-        (acc << 1) | matches!(character, 'B' | 'R') as u16
-    })
-}
-
-fn read_file(filename: &str) -> std::io::Result<Vec<String>> {
-    let input = fs::read_to_string(filename)?;
+        match taken {
+            true => println!(
+                "  neighbour {} (row {}, column {}) is taken by {}",
+                neighbour,
+                row,
+                column,
+                boarding_pass(neighbour)
+            ),
+            false => println!("  neighbour {} is missing too", neighbour),
+        }
+    }
 
-    Ok(input.lines().map(|line| line.to_string()).collect())
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The seat ids read straight from the boarding pass lines, one at a time.
+fn read_seats(filename: &str) -> io::Result<impl Iterator<Item = io::Result<u16>>> {
+    info!("streaming boarding passes from {}", filename);
 
-    #[test]
-    fn example_01() {
-        assert_eq!(calculate_seat(&"BFFFBBFRRR".to_string()), 567);
-        assert_eq!(calculate_seat(&"FFFBBBFRRR".to_string()), 119);
-        assert_eq!(calculate_seat(&"BBFFBBFRLL".to_string()), 820);
-    }
+    Ok(Input::open(filename)?.map_lines(calculate_seat))
 }
@@ -2,7 +2,11 @@ use std::fs;
 use std::time::Instant;
 use std::{collections::HashMap, error::Error};
 
+use aoc::{debug, info, log};
+
 fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
     let questions_list = read_file("input.txt")?;
 
     let start = Instant::now();
@@ -46,10 +50,19 @@ fn part_02(questions_list: &Vec<String>) -> usize {
             }
         }
 
-        count += answers[index]
+        let everyone = answers[index]
             .iter()
             .filter(|(_, &answer_count)| answer_count == group_size)
-            .count()
+            .count();
+
+        debug!(
+            "group {}: {} persons, {} questions answered by everyone",
+            index + 1,
+            group_size,
+            everyone
+        );
+
+        count += everyone
     }
 
     count
@@ -58,10 +71,14 @@ fn part_02(questions_list: &Vec<String>) -> usize {
 fn read_file(filename: &str) -> std::io::Result<Vec<String>> {
     let input = fs::read_to_string(filename)?;
 
-    Ok(input
+    let groups: Vec<String> = input
         .split("\r\n\r\n")
         .map(|line| line.to_string().replace("\r\n", " "))
-        .collect())
+        .collect();
+
+    info!("parsed {} groups from {}", groups.len(), filename);
+
+    Ok(groups)
 }
 
 #[cfg(test)]
@@ -2,8 +2,12 @@ use std::fs;
 use std::{collections::HashMap, time::Instant};
 use std::{error::Error, str::FromStr};
 
+use aoc::{debug, info, log, trace};
+
 // TODO: bleh refactor this solution bit messy.
 fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
     let rules = read_file("input.txt")?;
 
     let start = Instant::now();
@@ -32,7 +36,11 @@ fn part_02(rules: &Vec<String>, bag_color: Color) -> usize {
 fn read_file(filename: &str) -> std::io::Result<Vec<String>> {
     let input = fs::read_to_string(filename)?;
 
-    Ok(input.lines().map(|line| line.to_string()).collect())
+    let lines: Vec<String> = input.lines().map(|line| line.to_string()).collect();
+
+    info!("read {} rules from {}", lines.len(), filename);
+
+    Ok(lines)
 }
 
 type Color = String;
@@ -62,18 +70,23 @@ impl Rules {
                 .filter_map(|item| item.parse().ok())
                 .collect();
 
+            trace!("{} holds {} kinds of bags", color, items.len());
+
             content.insert(color, items);
         }
 
+        debug!("parsed {} bag rules", content.len());
+
         Rules { content }
     }
 
     fn bag_count_color(&self, bag_color: &String) -> usize {
         let mut bag_count = 0;
 
-        for (_, content) in self.content.iter() {
+        for (color, content) in self.content.iter() {
             let count = self.search_content(content, &bag_color.to_uppercase());
             if count > 0 {
+                trace!("{} eventually holds {} {}", color, count, bag_color);
                 bag_count += 1
             }
         }
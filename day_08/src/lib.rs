@@ -0,0 +1,159 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+// Logging needs the `std` feature, without it these expand to nothing.
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "std")]
+        aoc::debug!($($arg)+);
+    };
+}
+
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "std")]
+        aoc::trace!($($arg)+);
+    };
+}
+
+pub fn part_01(instructions: &[Instruction]) -> i32 {
+    let mut console = Console {
+        history: Vec::new(),
+        instructions: instructions.to_vec(),
+        accumulator: 0,
+    };
+
+    console.execute_instructions();
+
+    console.accumulator
+}
+
+pub fn part_02(instructions: &[Instruction]) -> i32 {
+    match repair(instructions) {
+        Some((_, accumulator)) => accumulator,
+        None => panic!("Could not fix the program."),
+    }
+}
+
+/// Flips the first `jmp`/`nop` that lets the program terminate, returning its index
+/// and the final accumulator.
+pub fn repair(instructions: &[Instruction]) -> Option<(usize, i32)> {
+    let mut console = Console {
+        history: Vec::new(),
+        instructions: instructions.to_vec(),
+        accumulator: 0,
+    };
+
+    console.execute_instructions();
+
+    debug!(
+        "original program loops after {} instructions",
+        console.history.len()
+    );
+
+    for &index in console.history.iter() {
+        let index = index as usize;
+        let mut new_instructions: Vec<Instruction> = console.instructions.clone();
+
+        if let Some(flipped) = new_instructions[index].flip() {
+            new_instructions[index] = flipped;
+
+            let mut new_console = Console {
+                history: Vec::new(),
+                instructions: new_instructions,
+                accumulator: 0,
+            };
+
+            let has_loop = new_console.execute_instructions();
+
+            if !has_loop {
+                debug!("flipping #{} terminates the program", index);
+
+                return Some((index, new_console.accumulator));
+            }
+
+            trace!("flipping #{} still loops", index);
+        }
+    }
+
+    None
+}
+
+#[derive(Debug)]
+pub struct Console {
+    pub history: Vec<i32>,
+    pub instructions: Vec<Instruction>,
+    pub accumulator: i32,
+}
+
+impl Console {
+    pub fn execute_instructions(&mut self) -> bool {
+        let mut index: i32 = 0;
+        let mut has_loop = false;
+
+        while (index as usize) < self.instructions.len() {
+            if self.history.contains(&index) {
+                has_loop = true;
+                break;
+            }
+
+            self.history.push(index);
+
+            let (next_index, next_acc) =
+                self.instructions[index as usize].execute(index, self.accumulator);
+
+            index = next_index;
+            self.accumulator = next_acc;
+        }
+
+        has_loop
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
+    Acc(i32),
+    Jmp(i32),
+    Nop(i32),
+}
+
+impl Instruction {
+    pub fn execute(&self, index: i32, acc: i32) -> (i32, i32) {
+        match self {
+            Instruction::Acc(arg) => (index + 1, acc + arg),
+            Instruction::Jmp(arg) => (index + arg, acc),
+            Instruction::Nop(_) => (index + 1, acc),
+        }
+    }
+
+    /// Swaps `jmp` and `nop`, `acc` can't be flipped.
+    pub fn flip(&self) -> Option<Instruction> {
+        match *self {
+            Instruction::Acc(_) => None,
+            Instruction::Jmp(arg) => Some(Instruction::Nop(arg)),
+            Instruction::Nop(arg) => Some(Instruction::Jmp(arg)),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = core::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split: Vec<&str> = s.split(' ').collect();
+
+        let instruction_string = split[0].to_uppercase();
+        let argument: i32 = split[1].parse()?;
+
+        Ok(match instruction_string.as_str() {
+            "ACC" => Instruction::Acc(argument),
+            "JMP" => Instruction::Jmp(argument),
+            "NOP" => Instruction::Nop(argument),
+            _ => panic!("Invalid instruction"),
+        })
+    }
+}
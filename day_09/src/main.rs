@@ -1,9 +1,16 @@
-use std::{cmp::Ordering, error::Error, fs, time::Instant};
+use std::{cmp::Ordering, env, error::Error, io, time::Instant};
+
+use aoc::input::Input;
+use aoc::{debug, info, log, trace};
 
 const PREAMBLE: usize = 25;
 
 // TODO: solutions can probably be more optimized, they are a bit slow.
 fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
+    let explain = env::args().any(|arg| arg == "--explain");
+
     let numbers = read_file("input.txt")?;
 
     let start = Instant::now();
@@ -13,46 +20,82 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("Answer 1: {:?}", invalid_number);
     println!("Completed in {:?}", start.elapsed());
 
+    if explain {
+        if let Some((index, number)) = find_invalid(&numbers, PREAMBLE) {
+            println!(
+                "  #{} ({}) is not the sum of two of the {} numbers before it",
+                index, number, PREAMBLE
+            );
+        }
+    }
+
     let start = Instant::now();
 
     println!("Answer 2: {:?}", part_02(&numbers, invalid_number.unwrap()));
     println!("Completed in {:?}", start.elapsed());
 
+    if explain {
+        if let Some((first, last)) = find_contiguous_range(&numbers, invalid_number.unwrap()) {
+            let range = &numbers[first..=last];
+
+            println!(
+                "  #{}..=#{} ({} numbers) sum to {}, smallest {} and largest {}",
+                first,
+                last,
+                range.len(),
+                invalid_number.unwrap(),
+                range.iter().min().unwrap(),
+                range.iter().max().unwrap()
+            );
+        }
+    }
+
     Ok(())
 }
 
-fn part_01(numbers: &Vec<u64>, preamble: usize) -> Option<u64> {
+fn part_01(numbers: &[u64], preamble: usize) -> Option<u64> {
+    find_invalid(numbers, preamble).map(|(_, number)| number)
+}
+
+fn part_02(numbers: &[u64], invalid_number: u64) -> Option<u64> {
+    find_contiguous_range(numbers, invalid_number).map(|(first, last)| {
+        let range = &numbers[first..=last];
+
+        range.iter().min().unwrap() + range.iter().max().unwrap()
+    })
+}
+
+/// Index and value of the first number that isn't the sum of two numbers in its preamble.
+fn find_invalid(numbers: &[u64], preamble: usize) -> Option<(usize, u64)> {
     for (index, number) in numbers.iter().enumerate().skip(preamble) {
-        let mut preamble: Vec<u64> = numbers[(index - preamble)..index].iter().copied().collect();
+        let mut preamble: Vec<u64> = numbers[(index - preamble)..index].to_vec();
 
         preamble.sort();
 
-        let is_valid = number_is_valid(&number, &preamble);
+        let is_valid = number_is_valid(number, &preamble);
 
         if !is_valid {
-            return Some(*number);
+            debug!("#{} ({}) is not valid", index, number);
+
+            return Some((index, *number));
         }
     }
 
     None
 }
 
-fn part_02(numbers: &Vec<u64>, invalid_number: u64) -> Option<u64> {
-    for (index, _) in numbers.iter().enumerate() {
-        let range: Vec<u64> = numbers[index..].iter().copied().collect();
+/// First and last index (inclusive) of the first contiguous range summing to `invalid_number`.
+fn find_contiguous_range(numbers: &[u64], invalid_number: u64) -> Option<(usize, usize)> {
+    for (first, _) in numbers.iter().enumerate() {
         let mut accumulator = 0;
 
-        for (index, number) in range.iter().enumerate() {
+        trace!("trying ranges starting at #{}", first);
+
+        for (index, number) in numbers[first..].iter().enumerate() {
             accumulator += number;
 
             match (accumulator).cmp(&invalid_number) {
-                Ordering::Equal => {
-                    let mut range: Vec<u64> = range[..=index].iter().copied().collect();
-
-                    range.sort();
-
-                    return Some(range[0] + range[range.len() - 1]);
-                }
+                Ordering::Equal => return Some((first, first + index)),
                 Ordering::Greater => break,
                 Ordering::Less => continue,
             }
@@ -62,7 +105,7 @@ fn part_02(numbers: &Vec<u64>, invalid_number: u64) -> Option<u64> {
     None
 }
 
-fn number_is_valid(number: &u64, preamble: &Vec<u64>) -> bool {
+fn number_is_valid(number: &u64, preamble: &[u64]) -> bool {
     for i in preamble {
         for j in preamble {
             match (i + j).cmp(number) {
@@ -73,13 +116,17 @@ fn number_is_valid(number: &u64, preamble: &Vec<u64>) -> bool {
         }
     }
 
-    return false;
+    false
 }
 
-fn read_file(filename: &str) -> std::io::Result<Vec<u64>> {
-    let input = fs::read_to_string(filename)?;
+fn read_file(filename: &str) -> io::Result<Vec<u64>> {
+    let numbers: Vec<u64> = Input::open(filename)?
+        .records()
+        .collect::<io::Result<_>>()?;
+
+    info!("parsed {} numbers from {}", numbers.len(), filename);
 
-    Ok(input.lines().filter_map(|line| line.parse().ok()).collect())
+    Ok(numbers)
 }
 
 #[cfg(test)]
@@ -91,6 +138,7 @@ mod tests {
         let numbers = read_file("example.txt").unwrap();
 
         assert_eq!(part_01(&numbers, 5), Some(127));
+        assert_eq!(find_invalid(&numbers, 5), Some((14, 127)));
     }
 
     #[test]
@@ -98,5 +146,6 @@ mod tests {
         let numbers = read_file("example.txt").unwrap();
 
         assert_eq!(part_02(&numbers, 127), Some(62));
+        assert_eq!(find_contiguous_range(&numbers, 127), Some((2, 5)));
     }
 }
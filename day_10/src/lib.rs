@@ -0,0 +1,59 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub fn part_01(voltage_ratings: &[u64]) -> u64 {
+    let differences = joltage_differences(voltage_ratings);
+
+    differences[1] * differences[3]
+}
+
+/// Histogram of the joltage differences in the full adapter chain, indexed by difference.
+pub fn joltage_differences(voltage_ratings: &[u64]) -> [u64; 4] {
+    let mut ratings: Vec<u64> = voltage_ratings.to_vec();
+    let mut differences = [0; 4];
+
+    ratings.push(0);
+    ratings.sort();
+    ratings.push(ratings[ratings.len() - 1] + 3);
+
+    let mut index = 0;
+    while index < ratings.len() - 1 {
+        let difference = ratings[index + 1] - ratings[index];
+
+        if let Some(count) = differences.get_mut(difference as usize) {
+            *count += 1;
+        }
+
+        index += 1;
+    }
+
+    differences
+}
+
+pub fn part_02(voltage_ratings: &[u64]) -> Option<u64> {
+    let mut ratings: Vec<u64> = voltage_ratings.to_vec();
+
+    ratings.push(0);
+    ratings.sort();
+
+    let mut methods: Vec<u64> = vec![0u64; ratings.len()];
+    methods[0] = 1;
+
+    for (start_index, start_rating) in ratings.iter().enumerate() {
+        let value = methods[start_index];
+
+        for (index, rating) in ratings[start_index..].iter().enumerate().skip(1) {
+            if rating - start_rating <= 3 {
+                methods[start_index + index] += value;
+            } else {
+                break;
+            }
+        }
+    }
+
+    methods.last().copied()
+}
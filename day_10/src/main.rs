@@ -1,13 +1,40 @@
-use std::{error::Error, fs, time::Instant};
+use std::{env, error::Error, io, time::Instant};
+
+use aoc::input::Input;
+use aoc::{debug, info, log};
+use day_10::{joltage_differences, part_01, part_02};
 
 fn main() -> Result<(), Box<dyn Error>> {
+    log::init();
+
+    let explain = env::args().any(|arg| arg == "--explain");
+
     let voltage_ratings = read_file("input.txt")?;
 
+    debug!(
+        "device rated at {} jolts",
+        voltage_ratings.iter().max().map(|max| max + 3).unwrap_or(3)
+    );
+
     let start = Instant::now();
 
     println!("Answer 1: {:?}", part_01(&voltage_ratings));
     println!("Completed in {:?}", start.elapsed());
 
+    if explain {
+        let differences = joltage_differences(&voltage_ratings);
+
+        for (difference, count) in differences.iter().enumerate().skip(1) {
+            println!("  {} jolt: {}", difference, count);
+        }
+        println!(
+            "  {} * {} = {}",
+            differences[1],
+            differences[3],
+            part_01(&voltage_ratings)
+        );
+    }
+
     let start = Instant::now();
 
     println!("Answer 2: {:?}", part_02(&voltage_ratings));
@@ -16,57 +43,14 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn part_01(voltage_ratings: &Vec<u64>) -> u64 {
-    let mut ratings: Vec<u64> = voltage_ratings.iter().copied().collect();
-    let mut count_1: u64 = 0;
-    let mut count_3: u64 = 0;
-
-    ratings.push(0);
-    ratings.sort();
-    ratings.push(ratings[ratings.len() - 1] + 3);
-
-    let mut index = 0;
-    while index < ratings.len() - 1 {
-        match ratings[index + 1] - ratings[index] {
-            1 => count_1 += 1,
-            3 => count_3 += 1,
-            _ => (),
-        }
-
-        index += 1;
-    }
-
-    count_1 * count_3
-}
-
-fn part_02(voltage_ratings: &Vec<u64>) -> Option<u64> {
-    let mut ratings: Vec<u64> = voltage_ratings.iter().copied().collect();
-
-    ratings.push(0);
-    ratings.sort();
-
-    let mut methods: Vec<u64> = vec![0u64; ratings.len()];
-    methods[0] = 1;
-
-    for (start_index, start_rating) in ratings.iter().enumerate() {
-        let value = methods[start_index];
-
-        for (index, rating) in ratings[start_index..].iter().enumerate().skip(1) {
-            if rating - start_rating <= 3 {
-                methods[start_index + index] += value;
-            } else {
-                break;
-            }
-        }
-    }
-
-    methods.last().copied()
-}
+fn read_file(filename: &str) -> io::Result<Vec<u64>> {
+    let ratings: Vec<u64> = Input::open(filename)?
+        .records()
+        .collect::<io::Result<_>>()?;
 
-fn read_file(filename: &str) -> std::io::Result<Vec<u64>> {
-    let input = fs::read_to_string(filename)?;
+    info!("parsed {} adapters from {}", ratings.len(), filename);
 
-    Ok(input.lines().filter_map(|line| line.parse().ok()).collect())
+    Ok(ratings)
 }
 
 #[cfg(test)]
@@ -78,6 +62,7 @@ mod tests {
         let ratings = read_file("example.txt").unwrap();
 
         assert_eq!(part_01(&ratings), 220);
+        assert_eq!(joltage_differences(&ratings), [0, 22, 0, 10]);
     }
 
     #[test]
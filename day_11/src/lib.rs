@@ -0,0 +1,189 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+// Logging needs the `std` feature, without it this expands to nothing.
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "std")]
+        aoc::debug!($($arg)+);
+    };
+}
+
+fn count_occupied_neighbors(grid: &[Vec<char>], row_index: usize, col_index: usize) -> u64 {
+    let movements = [
+        (-1, 0),
+        (1, 0),
+        (0, -1),
+        (0, 1),
+        (-1, 1),
+        (1, 1),
+        (-1, -1),
+        (1, -1),
+    ];
+
+    let mut count = 0;
+    for (row, col) in &movements {
+        let row_index = row_index as isize + row;
+        let col_index = col_index as isize + col;
+
+        // check if index is off grid
+        if is_on_grid(grid, row_index, col_index)
+            && grid[row_index as usize][col_index as usize] == '#'
+        {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn count_visible_occupied_neighbors(grid: &[Vec<char>], row_index: usize, col_index: usize) -> u64 {
+    let movements = [
+        (-1, 0),
+        (1, 0),
+        (0, -1),
+        (0, 1),
+        (-1, 1),
+        (1, 1),
+        (-1, -1),
+        (1, -1),
+    ];
+
+    let mut count = 0;
+    for (row, col) in &movements {
+        let mut n = 1;
+
+        loop {
+            let row_index = row_index as isize + row * n;
+            let col_index = col_index as isize + col * n;
+
+            // check if index is off grid
+            if is_on_grid(grid, row_index, col_index) {
+                match grid[row_index as usize][col_index as usize] {
+                    '#' => {
+                        count += 1;
+                        break;
+                    }
+                    'L' => break,
+                    _ => (),
+                }
+
+                n += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    count
+}
+
+fn is_on_grid(grid: &[Vec<char>], row_index: isize, col_index: isize) -> bool {
+    if row_index >= 0
+        && row_index < (grid.len() as isize)
+        && col_index >= 0
+        && col_index < (grid[row_index as usize].len() as isize)
+    {
+        return true;
+    }
+
+    false
+}
+
+pub fn part_01(rows: &[Vec<char>]) -> usize {
+    stabilise(rows, generate_next).occupied
+}
+
+pub fn part_02(rows: &[Vec<char>]) -> usize {
+    stabilise(rows, generate_next_visible).occupied
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Stabilised {
+    pub occupied: usize,
+    pub generations: usize,
+}
+
+/// Stabilises the seating with the part 1 (adjacent seats) rules.
+pub fn stabilise_adjacent(rows: &[Vec<char>]) -> Stabilised {
+    stabilise(rows, generate_next)
+}
+
+/// Stabilises the seating with the part 2 (visible seats) rules.
+pub fn stabilise_visible(rows: &[Vec<char>]) -> Stabilised {
+    stabilise(rows, generate_next_visible)
+}
+
+fn stabilise(rows: &[Vec<char>], generate: fn(&[Vec<char>]) -> Vec<Vec<char>>) -> Stabilised {
+    let mut current = rows.to_vec();
+    let mut next: Vec<Vec<char>>;
+    let mut generations = 0;
+
+    loop {
+        next = generate(&current);
+
+        debug!(
+            "generation {}: {} seats changed",
+            generations + 1,
+            current
+                .iter()
+                .flatten()
+                .zip(next.iter().flatten())
+                .filter(|(before, after)| before != after)
+                .count()
+        );
+
+        if next == current {
+            return Stabilised {
+                occupied: current
+                    .iter()
+                    .flatten()
+                    .filter(|seat| **seat == '#')
+                    .count(),
+                generations,
+            };
+        }
+
+        current = next;
+        generations += 1;
+    }
+}
+
+fn generate_next(rows: &[Vec<char>]) -> Vec<Vec<char>> {
+    let mut next = rows.to_vec();
+
+    for (row_index, row) in rows.iter().enumerate() {
+        for (seat_index, seat) in row.iter().enumerate() {
+            let new_state = match *seat {
+                'L' if count_occupied_neighbors(rows, row_index, seat_index) == 0 => '#',
+                '#' if count_occupied_neighbors(rows, row_index, seat_index) >= 4 => 'L',
+                c => c,
+            };
+
+            next[row_index][seat_index] = new_state;
+        }
+    }
+
+    next
+}
+
+fn generate_next_visible(rows: &[Vec<char>]) -> Vec<Vec<char>> {
+    let mut next = rows.to_vec();
+
+    for (row_index, row) in rows.iter().enumerate() {
+        for (seat_index, seat) in row.iter().enumerate() {
+            let new_state = match *seat {
+                'L' if count_visible_occupied_neighbors(rows, row_index, seat_index) == 0 => '#',
+                '#' if count_visible_occupied_neighbors(rows, row_index, seat_index) >= 5 => 'L',
+                c => c,
+            };
+
+            next[row_index][seat_index] = new_state;
+        }
+    }
+
+    next
+}